@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use ggez::{GameError, GameResult};
+
+use rust_tictactoe::ttt::cli::{arg_value, parse_arg};
+use rust_tictactoe::ttt::engine;
+use rust_tictactoe::ttt::game::{Cell, Player};
+use rust_tictactoe::ttt::rating::{RatingStore, Ratings};
+use rust_tictactoe::ttt::record::{self, GameRecord};
+use rust_tictactoe::ttt::tournament;
+
+static USAGE: &str =
+    "usage: tournament <engine> <engine> [--games <n>] [--records <file>] [--ratings <file>]
+       tournament --replay <file>
+       tournament --leaderboard <ratings file>
+       tournament --rebuild-ratings <records file> --ratings <file>
+
+engines: minimax[:depth], random, mcts[:iterations], perfect, external:<command>";
+
+fn print_board(cells: &[Cell]) {
+    for row in cells.chunks(3) {
+        let line: String = row
+            .iter()
+            .map(|cell| match cell {
+                Cell::Empty => '.',
+                Cell::Blocked => '*',
+                Cell::Player(Player::Player1) => 'O',
+                Cell::Player(Player::Player2) => 'X',
+            })
+            .collect();
+        println!("{}", line);
+    }
+    println!();
+}
+
+fn replay(records: &[GameRecord]) {
+    for (i, record) in records.iter().enumerate() {
+        let result = match record.winner {
+            Some(Player::Player1) => format!("{} won", record.player1),
+            Some(Player::Player2) => format!("{} won", record.player2),
+            None => String::from("draw"),
+        };
+        println!(
+            "game {}: {} (O) vs {} (X), {}",
+            i + 1,
+            record.player1,
+            record.player2,
+            result
+        );
+        for cells in record.replay().iter().skip(1) {
+            print_board(cells);
+        }
+    }
+}
+
+fn print_leaderboard(ratings: &Ratings) {
+    println!(
+        "{:<4} {:<24} {:>7} {:>6} {:>6} {:>6}",
+        "#", "name", "rating", "wins", "draws", "losses"
+    );
+    for (i, (name, rating)) in ratings.leaderboard().iter().enumerate() {
+        println!(
+            "{:<4} {:<24} {:>7.0} {:>6} {:>6} {:>6}",
+            i + 1,
+            name,
+            rating.elo,
+            rating.wins,
+            rating.draws,
+            rating.losses
+        );
+    }
+}
+
+fn main() -> GameResult {
+    let args: Vec<String> = std::env::args().collect();
+
+    if let Some(path) = arg_value(&args, "--leaderboard") {
+        print_leaderboard(RatingStore::open(PathBuf::from(path), None)?.ratings());
+        return Ok(());
+    }
+    if let Some(path) = arg_value(&args, "--rebuild-ratings") {
+        let ratings_path = arg_value(&args, "--ratings")
+            .ok_or_else(|| GameError::ConfigError(String::from(USAGE)))?;
+        let records = record::read_records(BufReader::new(File::open(path)?))?;
+        let ratings = Ratings::from_records(&records);
+        ratings.write_to(&mut File::create(ratings_path)?)?;
+        print_leaderboard(&ratings);
+        return Ok(());
+    }
+
+    if let Some(path) = arg_value(&args, "--replay") {
+        replay(&record::read_records(BufReader::new(File::open(path)?))?);
+        return Ok(());
+    }
+
+    // Positional arguments are the ones that neither are an option nor follow one.
+    let engines: Vec<&String> = args
+        .iter()
+        .skip(1)
+        .enumerate()
+        .filter(|&(i, arg)| !arg.starts_with("--") && !args[i].starts_with("--"))
+        .map(|(_, arg)| arg)
+        .collect();
+    if engines.len() != 2 {
+        return Err(GameError::ConfigError(String::from(USAGE)));
+    }
+    let mut a = engine::from_spec(engines[0]).map_err(GameError::ConfigError)?;
+    let mut b = engine::from_spec(engines[1]).map_err(GameError::ConfigError)?;
+    let games = parse_arg(&args, "--games", 100)?;
+
+    let (stats, records) = tournament::run(a.as_mut(), b.as_mut(), games);
+
+    println!("{} vs {}: {} games", a.name(), b.name(), stats.games);
+    for (i, engine) in [&a, &b].iter().enumerate() {
+        println!(
+            "  {:<20} wins {:>5}  draws {:>5}  losses {:>5}  avg move {:?}",
+            engine.name(),
+            stats.wins[i],
+            stats.draws,
+            stats.losses(i),
+            stats.average_move_time(i)
+        );
+    }
+    println!("  average game length: {:.2} plies", stats.average_length());
+
+    if let Some(path) = arg_value(&args, "--records") {
+        record::write_records(&mut File::create(path)?, &records)?;
+    }
+    if let Some(path) = arg_value(&args, "--ratings") {
+        let mut store = RatingStore::open(PathBuf::from(path), None)?;
+        for record in &records {
+            store.record(record)?;
+        }
+        println!();
+        print_leaderboard(store.ratings());
+    }
+    Ok(())
+}
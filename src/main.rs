@@ -1,20 +1,229 @@
-use ggez::event;
+use std::path::PathBuf;
 
-use ggez::GameResult;
+use ggez::event;
 
-mod ttt;
+use ggez::{GameError, GameResult};
 
-use ttt::game::{Game};
+use rust_tictactoe::ttt::app::{App, Setup};
+use rust_tictactoe::ttt::cli::{arg_value, parse_arg};
+use rust_tictactoe::ttt::game::Topology;
+use rust_tictactoe::ttt::game_logic::{Difficulty, TieBreak};
+use rust_tictactoe::ttt::opening_book::{self, OpeningBook};
+use rust_tictactoe::ttt::puzzle;
+use rust_tictactoe::ttt::rng::Rng;
+use rust_tictactoe::ttt::settings::{self, Settings};
+use rust_tictactoe::ttt::shape::BoardShape;
+use rust_tictactoe::ttt::stats::{self, History};
+use rust_tictactoe::ttt::tablebase::{self, Tablebase};
+use rust_tictactoe::ttt::theme;
 
-use ttt::config::{GAME_NAME, AUTHOR, SCREEN_SIZE};
+use rust_tictactoe::ttt::config::{
+    AI_BOOK_VARIETY, AI_TIE_BREAK, AUTHOR, GAME_NAME, MULTI_PLAYERS, SCREEN_SIZE,
+};
 
 fn main() -> GameResult {
-    // Make a Context.
-    let (ctx, event_loop) = &mut ggez::ContextBuilder::new(GAME_NAME, AUTHOR)
+    let args: Vec<String> = std::env::args().collect();
+
+    if let Some(path) = arg_value(&args, "--write-tablebase") {
+        let mut file = std::fs::File::create(path)?;
+        Tablebase::solve().write_to(&mut file)?;
+        return Ok(());
+    }
+    if let Some(path) = arg_value(&args, "--tablebase") {
+        let mut file = std::fs::File::open(path)?;
+        tablebase::install(Tablebase::read_from(&mut file)?);
+    }
+
+    if let Some(path) = arg_value(&args, "--write-book") {
+        let plies = parse_arg(&args, "--book-plies", 4)?;
+        let book = match arg_value(&args, "--book-source").as_deref() {
+            None | Some("solver") => OpeningBook::from_solver(plies),
+            Some("self-play") => {
+                let games = parse_arg(&args, "--book-games", 1000)?;
+                OpeningBook::from_self_play(games, plies, &mut Rng::from_time())
+            }
+            Some(source) => {
+                return Err(GameError::ConfigError(format!(
+                    "unknown book source '{}'",
+                    source
+                )))
+            }
+        };
+        book.write_to(&mut std::fs::File::create(path)?)?;
+        return Ok(());
+    }
+    if let Some(path) = arg_value(&args, "--write-puzzles") {
+        let moves = parse_arg(&args, "--puzzle-moves", 2)?;
+        let puzzles = match arg_value(&args, "--puzzle-source").as_deref() {
+            None | Some("solver") => puzzle::mine_from_solver(moves),
+            Some("self-play") => {
+                let games = parse_arg(&args, "--puzzle-games", 1000)?;
+                puzzle::mine_from_self_play(games, moves, &mut Rng::from_time())
+            }
+            Some(source) => {
+                return Err(GameError::ConfigError(format!(
+                    "unknown puzzle source '{}'",
+                    source
+                )))
+            }
+        };
+        puzzle::write_puzzles(&mut std::fs::File::create(path)?, &puzzles)?;
+        return Ok(());
+    }
+
+    if let Some(path) = arg_value(&args, "--book") {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        opening_book::install(OpeningBook::read_from(file)?);
+    }
+
+    let tie_break = match arg_value(&args, "--tie-break") {
+        Some(policy) => policy.parse::<TieBreak>().map_err(GameError::ConfigError)?,
+        None => AI_TIE_BREAK,
+    };
+
+    let book_variety = parse_arg(&args, "--book-variety", AI_BOOK_VARIETY)?;
+
+    let settings_path = arg_value(&args, "--settings")
+        .map(PathBuf::from)
+        .or_else(settings::default_settings_path);
+    // Settings that couldn't be read aren't saved over, so nothing in the
+    // file is lost.
+    let (settings, settings_path) = match settings_path {
+        Some(path) => match Settings::load(&path) {
+            Ok(settings) => (settings, Some(path)),
+            Err(e) => {
+                eprintln!("Can't read settings, changes won't be saved: {}", e);
+                (Settings::default(), None)
+            }
+        },
+        None => (Settings::default(), None),
+    };
+
+    let themes_dir = arg_value(&args, "--themes")
+        .map(PathBuf::from)
+        .or_else(settings::default_themes_dir);
+    let themes = match &themes_dir {
+        Some(dir) => theme::load_themes(dir).unwrap_or_else(|e| {
+            eprintln!("Can't read themes: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+
+    let difficulty = match arg_value(&args, "--difficulty") {
+        Some(difficulty) => difficulty
+            .parse::<Difficulty>()
+            .map_err(GameError::ConfigError)?,
+        None => settings.difficulty,
+    };
+
+    let history_path = arg_value(&args, "--history")
+        .map(PathBuf::from)
+        .or_else(stats::default_history_path);
+    if let Some(path) = arg_value(&args, "--export-csv") {
+        let history_path = history_path
+            .ok_or_else(|| GameError::ConfigError(String::from("no game history to export")))?;
+        let history = History::open(history_path)?;
+        stats::write_csv(&mut std::fs::File::create(path)?, history.entries())?;
+        return Ok(());
+    }
+
+    let mut setup = Setup {
+        difficulty,
+        first: settings.first,
+        animation_speed: settings.animation_speed,
+        tie_break,
+        book_variety,
+        history: history_path,
+        ..Setup::default()
+    };
+    let in_game = match arg_value(&args, "--variant") {
+        Some(variant) => {
+            setup.variant = variant.parse().map_err(GameError::ConfigError)?;
+            true
+        }
+        None => false,
+    };
+
+    setup.topology = match arg_value(&args, "--topology").as_deref() {
+        None | Some("flat") => Topology::Flat,
+        Some("torus") => Topology::Torus,
+        Some(topology) => {
+            return Err(GameError::ConfigError(format!(
+                "unknown topology '{}'",
+                topology
+            )))
+        }
+    };
+    if let Some(path) = arg_value(&args, "--shape") {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        setup.shape = BoardShape::read_from(file)?;
+    }
+
+    if let Some(size) = arg_value(&args, "--board") {
+        setup.board = Some(parse_board_size(&size)?);
+    }
+    if let Some(connect) = arg_value(&args, "--connect") {
+        setup.connect = Some(connect.parse().map_err(|_| {
+            GameError::ConfigError(format!("bad value '{}' for --connect", connect))
+        })?);
+    }
+    setup.players = parse_arg(&args, "--players", MULTI_PLAYERS)?;
+
+    if let Some(path) = arg_value(&args, "--puzzles") {
+        setup.puzzles = puzzle::read_puzzles(std::io::BufReader::new(std::fs::File::open(path)?))?;
+    }
+    setup.solved = arg_value(&args, "--solved").map(PathBuf::from);
+
+    if let Some(games) = arg_value(&args, "--match") {
+        match games.parse::<usize>() {
+            Ok(games) if games > 0 => setup.match_games = Some(games),
+            _ => {
+                return Err(GameError::ConfigError(format!(
+                    "bad number of match games '{}'",
+                    games
+                )))
+            }
+        }
+    }
+    if let Some(control) = arg_value(&args, "--clock") {
+        setup.clock = Some(control.parse().map_err(GameError::ConfigError)?);
+    }
+    setup.ratings = arg_value(&args, "--ratings").map(PathBuf::from);
+    setup.records = arg_value(&args, "--records").map(PathBuf::from);
+    if let Some(name) = arg_value(&args, "--name") {
+        setup.player_name = name;
+    }
+    setup.check().map_err(GameError::ConfigError)?;
+
+    // Build the app before the window, so bad options fail without one.
+    let app = &mut App::new(setup, settings, settings_path, themes, in_game)?;
+
+    // Make a Context. Theme images are looked up in `resources` and next
+    // to the theme files.
+    let mut context_builder = ggez::ContextBuilder::new(GAME_NAME, AUTHOR)
         .window_setup(ggez::conf::WindowSetup::default().title(GAME_NAME))
-        .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_SIZE.0, SCREEN_SIZE.1))
-        .build()?;
+        .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_SIZE.0, SCREEN_SIZE.1));
+    if let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+        context_builder =
+            context_builder.add_resource_path(PathBuf::from(manifest_dir).join("resources"));
+    }
+    if let Some(dir) = themes_dir {
+        context_builder = context_builder.add_resource_path(dir);
+    }
+    let (ctx, event_loop) = &mut context_builder.build()?;
+
+    event::run(ctx, event_loop, app)
+}
 
-    let state = &mut Game::new();
-    event::run(ctx, event_loop, state)
+/// Board size written as `<columns>x<rows>`, e.g. `7x6`.
+fn parse_board_size(size: &str) -> GameResult<(usize, usize)> {
+    let bad_size = || GameError::ConfigError(format!("bad board size '{}'", size));
+    let (columns, rows) = size.split_once('x').ok_or_else(bad_size)?;
+    let columns: usize = columns.parse().map_err(|_| bad_size())?;
+    let rows: usize = rows.parse().map_err(|_| bad_size())?;
+    if !(2..=20).contains(&columns) || !(2..=20).contains(&rows) {
+        return Err(bad_size());
+    }
+    Ok((columns, rows))
 }
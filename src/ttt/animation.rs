@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use super::config::{ANIMATION_SPEED, BOARD_CLEAR_SECS, MARK_DRAW_SECS, WIN_LINE_SECS};
+use super::game::Player;
+
+/// How far the marks, the winning line and the clearing of the previous
+/// board have been drawn, each from 0 to 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardAnimation {
+    /// Multiplies the speed; 0 draws everything at once.
+    speed: f32,
+    /// Progress of the mark in each cell, none while it is empty. Marks
+    /// waiting for an earlier one to be drawn start below 0.
+    marks: Vec<Option<f32>>,
+    win_line: f32,
+    /// Marks of the board just cleared, fading out as `clearing` goes to 1.
+    cleared: Vec<(usize, Player)>,
+    clearing: f32,
+}
+
+impl BoardAnimation {
+    pub fn new(cells: usize) -> Self {
+        BoardAnimation {
+            speed: ANIMATION_SPEED,
+            marks: vec![None; cells],
+            win_line: 0.0,
+            cleared: Vec::new(),
+            clearing: 1.0,
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Moves on by `elapsed`. `marks` are the occupied cells in the order
+    /// they were played; new ones are drawn one after the other once the
+    /// previous board has cleared, and a winning line once they are all
+    /// drawn.
+    pub fn advance(&mut self, elapsed: Duration, marks: &[usize], won: bool) {
+        let step = |secs: f32| {
+            if self.speed > 0.0 {
+                elapsed.as_secs_f32() * self.speed / secs
+            } else {
+                f32::INFINITY
+            }
+        };
+        let (mark_step, line_step, clear_step) = (
+            step(MARK_DRAW_SECS),
+            step(WIN_LINE_SECS),
+            step(BOARD_CLEAR_SECS),
+        );
+
+        let mut drawn = vec![None; self.marks.len()];
+        let mut waiting = 0.0;
+        for &cell in marks {
+            if drawn[cell].is_some() {
+                continue;
+            }
+            drawn[cell] = Some(self.marks[cell].unwrap_or_else(|| {
+                waiting += 1.0;
+                1.0 - waiting
+            }));
+        }
+        self.marks = drawn;
+
+        if self.clearing < 1.0 {
+            self.clearing = (self.clearing + clear_step).min(1.0);
+            if self.clearing == 1.0 {
+                self.cleared.clear();
+            }
+            return;
+        }
+        for progress in self.marks.iter_mut().flatten() {
+            *progress = (*progress + mark_step).min(1.0);
+        }
+        let all_drawn = self.marks.iter().flatten().all(|&progress| progress == 1.0);
+        self.win_line = match (won, all_drawn) {
+            (true, true) => (self.win_line + line_step).min(1.0),
+            (true, false) => self.win_line,
+            (false, _) => 0.0,
+        };
+    }
+
+    /// Starts fading out `marks`, those of the board that was just cleared.
+    pub fn clear_board(&mut self, marks: Vec<(usize, Player)>) {
+        self.marks.iter_mut().for_each(|progress| *progress = None);
+        self.win_line = 0.0;
+        if self.speed > 0.0 && !marks.is_empty() {
+            self.cleared = marks;
+            self.clearing = 0.0;
+        }
+    }
+
+    pub fn mark(&self, cell: usize) -> f32 {
+        self.marks[cell].map_or(0.0, |progress| progress.max(0.0))
+    }
+
+    pub fn win_line(&self) -> f32 {
+        self.win_line
+    }
+
+    /// The marks of the previous board still fading out, and how far.
+    pub fn cleared(&self) -> (&[(usize, Player)], f32) {
+        (&self.cleared, self.clearing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_are_drawn_in_turn_before_the_winning_line() {
+        let mut animation = BoardAnimation::new(9);
+        let frame = Duration::from_secs_f32(MARK_DRAW_SECS / 2.0);
+        animation.advance(frame, &[4, 0], true);
+        assert!((animation.mark(4) - 0.5).abs() < 1e-3);
+        assert_eq!(animation.mark(0), 0.0);
+        assert_eq!(animation.win_line(), 0.0);
+        animation.advance(frame * 4, &[4, 0], true);
+        assert_eq!(animation.mark(0), 1.0);
+        assert!(animation.win_line() > 0.0);
+
+        animation.clear_board(vec![(4, Player::Player1)]);
+        animation.advance(frame, &[2], false);
+        assert_eq!(animation.cleared().0.len(), 1);
+        assert_eq!(animation.mark(2), 0.0);
+
+        animation.set_speed(0.0);
+        animation.advance(Duration::from_millis(1), &[2, 6], true);
+        assert_eq!(animation.cleared().0.len(), 0);
+        animation.advance(Duration::from_millis(1), &[2, 6], true);
+        assert_eq!((animation.mark(6), animation.win_line()), (1.0, 1.0));
+    }
+}
@@ -0,0 +1,853 @@
+use std::path::PathBuf;
+
+use ggez::{
+    audio::SoundData,
+    event::{KeyCode, KeyMods, MouseButton},
+    Context, GameError, GameResult,
+};
+
+use super::clock::TimeControl;
+use super::config::{
+    AI_BOOK_VARIETY, AI_DIFFICULTY, AI_TIE_BREAK, ANIMATION_SPEED, GAME_NAME, GRAVITY_BOARD_SIZE,
+    GRAVITY_CONNECT, MULTI_BOARD_SIZE, MULTI_CONNECT, MULTI_PLAYERS,
+};
+use super::draw_helpers::Painter;
+use super::game::{Game, Mark, Player, RuleSet, Topology};
+use super::game_logic::{Ai, Difficulty, TieBreak};
+use super::gravity::GravityGame;
+use super::keys::{KeyBindings, KeyPreset, Stick};
+use super::multiplayer::{MultiGame, MAX_PLAYERS};
+use super::numerical::NumericalGame;
+use super::puzzle::{self, Puzzle, PuzzleGame, SolvedPuzzles};
+use super::qubic::QubicGame;
+use super::rating::{self, RatingStore};
+use super::settings::{Settings, ANIMATION_SPEEDS, VOLUMES};
+use super::shape::BoardShape;
+use super::sound;
+use super::stats::{History, Statistics};
+use super::theme::{self, Theme};
+use super::ultimate::UltimateGame;
+use super::wild::WildGame;
+
+/// The games that can be played.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    Misere,
+    Vanishing,
+    Wild,
+    Numerical,
+    Ultimate,
+    Qubic,
+    Gravity,
+    Multi,
+    Puzzle,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 10] = [
+        Variant::Classic,
+        Variant::Misere,
+        Variant::Vanishing,
+        Variant::Wild,
+        Variant::Numerical,
+        Variant::Ultimate,
+        Variant::Qubic,
+        Variant::Gravity,
+        Variant::Multi,
+        Variant::Puzzle,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Classic => "classic",
+            Variant::Misere => "misere",
+            Variant::Vanishing => "vanishing",
+            Variant::Wild => "wild",
+            Variant::Numerical => "numerical",
+            Variant::Ultimate => "ultimate",
+            Variant::Qubic => "qubic",
+            Variant::Gravity => "gravity",
+            Variant::Multi => "multi",
+            Variant::Puzzle => "puzzle",
+        }
+    }
+
+    /// Classic and misère are played on the board that topologies and
+    /// shapes change.
+    fn has_classic_board(self) -> bool {
+        self == Variant::Classic || self == Variant::Misere
+    }
+}
+
+impl std::str::FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variant::ALL
+            .iter()
+            .copied()
+            .find(|variant| variant.name() == s)
+            .ok_or_else(|| format!("unknown variant '{}'", s))
+    }
+}
+
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Everything a new game is started with, from the command line and the
+/// menus.
+#[derive(Clone, Debug)]
+pub struct Setup {
+    pub variant: Variant,
+    pub difficulty: Difficulty,
+    /// Who moves first on the classic board.
+    pub first: Player,
+    pub tie_break: TieBreak,
+    pub book_variety: f32,
+    pub topology: Topology,
+    pub animation_speed: f32,
+    pub shape: BoardShape,
+    /// Best-of-N match length.
+    pub match_games: Option<usize>,
+    pub clock: Option<TimeControl>,
+    /// Columns and rows for gravity and multi, their defaults if `None`.
+    pub board: Option<(usize, usize)>,
+    pub connect: Option<usize>,
+    pub players: usize,
+    pub puzzles: Vec<Puzzle>,
+    pub solved: Option<PathBuf>,
+    pub history: Option<PathBuf>,
+    pub ratings: Option<PathBuf>,
+    pub records: Option<PathBuf>,
+    pub player_name: String,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            variant: Variant::Classic,
+            difficulty: AI_DIFFICULTY,
+            first: Player::Player2,
+            tie_break: AI_TIE_BREAK,
+            book_variety: AI_BOOK_VARIETY,
+            topology: Topology::Flat,
+            animation_speed: ANIMATION_SPEED,
+            shape: BoardShape::default(),
+            match_games: None,
+            clock: None,
+            board: None,
+            connect: None,
+            players: MULTI_PLAYERS,
+            puzzles: puzzle::builtin(),
+            solved: None,
+            history: None,
+            ratings: None,
+            records: None,
+            player_name: String::from("player"),
+        }
+    }
+}
+
+impl Setup {
+    /// Columns, rows and the line length to connect for the variant.
+    pub fn board(&self) -> (usize, usize, usize) {
+        let (default_size, default_connect) = match self.variant {
+            Variant::Multi => (MULTI_BOARD_SIZE, MULTI_CONNECT),
+            _ => (GRAVITY_BOARD_SIZE, GRAVITY_CONNECT),
+        };
+        let (width, height) = self.board.unwrap_or(default_size);
+        (width, height, self.connect.unwrap_or(default_connect))
+    }
+
+    /// Why the variant can't be played with these options, if it can't.
+    pub fn check(&self) -> Result<(), String> {
+        if self.topology == Topology::Torus && !self.variant.has_classic_board() {
+            return Err(format!(
+                "topology 'torus' is not available for {}",
+                self.variant
+            ));
+        }
+        if self.shape != BoardShape::default() {
+            if !self.variant.has_classic_board() {
+                return Err(format!(
+                    "board shapes are not available for {}",
+                    self.variant
+                ));
+            }
+            if self.shape.has_own_lines() && self.topology == Topology::Torus {
+                return Err(String::from(
+                    "a board shape with its own lines can't be played on a torus",
+                ));
+            }
+        }
+        let (width, height, connect) = self.board();
+        if connect < 2 || connect > width.max(height) {
+            return Err(format!(
+                "can't connect {} on a {}x{} board",
+                connect, width, height
+            ));
+        }
+        if !(2..=MAX_PLAYERS).contains(&self.players) {
+            return Err(format!("can't seat {} players", self.players));
+        }
+        if self.variant == Variant::Puzzle && self.puzzles.is_empty() {
+            return Err(String::from("no puzzles to play"));
+        }
+        if self.clock.is_some() && self.variant == Variant::Vanishing {
+            // The vanishing search runs to the end, it can't be held to a budget.
+            return Err(String::from("clocks are not available for vanishing"));
+        }
+        rating::check_name(&self.player_name)?;
+        Ok(())
+    }
+
+    pub(crate) fn build(&self) -> GameResult<Box<dyn Mode>> {
+        self.check().map_err(GameError::ConfigError)?;
+        let (width, height, connect) = self.board();
+        let rules = match self.variant {
+            Variant::Classic => RuleSet::Standard,
+            Variant::Misere => RuleSet::Misere,
+            Variant::Vanishing => RuleSet::Vanishing,
+            Variant::Wild => return Ok(Box::new(WildGame::new())),
+            Variant::Numerical => return Ok(Box::new(NumericalGame::new())),
+            Variant::Ultimate => return Ok(Box::new(UltimateGame::new())),
+            Variant::Qubic => return Ok(Box::new(QubicGame::new())),
+            Variant::Gravity => {
+                let mut game = GravityGame::new(width, height, connect);
+                game.set_animation_speed(self.animation_speed);
+                return Ok(Box::new(game));
+            }
+            Variant::Multi => {
+                return Ok(Box::new(MultiGame::new(
+                    width,
+                    height,
+                    connect,
+                    self.players,
+                )))
+            }
+            Variant::Puzzle => {
+                let solved = match &self.solved {
+                    Some(path) => SolvedPuzzles::open(path.clone())?,
+                    None => SolvedPuzzles::default(),
+                };
+                return Ok(Box::new(PuzzleGame::new(self.puzzles.clone(), solved)));
+            }
+        };
+
+        let mut ai = Ai::new(self.tie_break, self.book_variety);
+        ai.difficulty = self.difficulty;
+        let mut game = Game::new(ai, rules);
+        game.set_animation_speed(self.animation_speed);
+        game.set_topology(self.topology);
+        game.set_shape(self.shape.clone());
+        if let Some(games) = self.match_games {
+            game.set_series(games);
+        }
+        if let Some(control) = self.clock {
+            game.set_clock(control);
+        }
+        // Only the classic board keeps a history: the statistics are of its
+        // cells and difficulties, which the other variants don't have.
+        if let Some(path) = &self.history {
+            match History::open(path.clone()) {
+                Ok(history) => game.set_history(history),
+                Err(e) => eprintln!("Can't read game history: {}", e),
+            }
+        }
+        if let Some(path) = &self.ratings {
+            let ratings = RatingStore::open(path.clone(), self.records.clone())?;
+            game.set_ratings(ratings, self.player_name.clone());
+        }
+        game.set_first(self.first);
+        Ok(Box::new(game))
+    }
+}
+
+/// A game the scenes can be wrapped around, getting the events the app
+/// passes on while it is played.
+pub(crate) trait Mode {
+    fn update(&mut self, ctx: &mut Context) -> GameResult;
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32);
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32);
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymod: KeyMods,
+        repeat: bool,
+    );
+
+    /// Draws the game with `painter` without presenting the frame, so pause
+    /// and game-over menus can be drawn over it.
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult;
+
+    /// What the game-over screen shows once the game has ended.
+    fn result(&self) -> Option<String>;
+
+    /// Whether the board is still being drawn; the game-over screen waits
+    /// for it. Only the classic board is animated.
+    fn is_animating(&self) -> bool {
+        false
+    }
+
+    /// Starts the next game.
+    fn restart(&mut self);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Scene {
+    MainMenu,
+    NewGame,
+    InGame,
+    Pause,
+    GameOver,
+    Settings,
+    Statistics,
+}
+
+/// What picking a menu item does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+    Continue,
+    NewGame,
+    Settings,
+    Statistics,
+    Quit,
+    Variant,
+    Difficulty,
+    First,
+    Mark,
+    Theme,
+    Animation,
+    Volume,
+    Keys,
+    Start,
+    Resume,
+    Restart,
+    MainMenu,
+    Back,
+}
+
+impl Action {
+    /// Options go through their values with Left and Right.
+    pub(crate) fn is_option(self) -> bool {
+        !matches!(
+            self,
+            Action::Continue
+                | Action::NewGame
+                | Action::Settings
+                | Action::Statistics
+                | Action::Quit
+                | Action::Start
+                | Action::Resume
+                | Action::Restart
+                | Action::MainMenu
+                | Action::Back
+        )
+    }
+}
+
+/// The entry after `current` in `all`, `step` entries on and wrapping round.
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, step: isize) -> T {
+    let index = all.iter().position(|&item| item == current).unwrap_or(0);
+    all[(index as isize + step).rem_euclid(all.len() as isize) as usize]
+}
+
+/// The theme called `name`, the classic one if there is none.
+fn theme_named(themes: &[(String, Theme)], name: &str) -> Theme {
+    themes
+        .iter()
+        .find(|(known, _)| known == name)
+        .map_or(Theme::CLASSIC, |(_, theme)| theme.clone())
+}
+
+/// The scene being shown and the game played in it.
+pub struct App {
+    setup: Setup,
+    settings: Settings,
+    /// Where changed settings are saved, if anywhere.
+    settings_path: Option<PathBuf>,
+    bindings: KeyBindings,
+    /// The built-in themes and those loaded from files, by name.
+    themes: Vec<(String, Theme)>,
+    painter: Painter,
+    stick: Stick,
+    click: SoundData,
+    chime: SoundData,
+    scene: Scene,
+    selected: usize,
+    game: Option<Box<dyn Mode>>,
+    statistics: Option<Statistics>,
+    /// Shown under the menu, e.g. why a game couldn't be started.
+    message: Option<String>,
+}
+
+impl App {
+    /// Starts in the main menu, or straight in a game of `setup` if
+    /// `in_game`. `settings` only change the setup from the settings screen
+    /// on, so options given on the command line win. `themes` come on top of
+    /// the built-in ones, replacing those of the same name.
+    pub fn new(
+        setup: Setup,
+        settings: Settings,
+        settings_path: Option<PathBuf>,
+        themes: Vec<(String, Theme)>,
+        in_game: bool,
+    ) -> GameResult<Self> {
+        let mut all_themes = theme::builtin();
+        for (name, theme) in themes {
+            match all_themes.iter_mut().find(|(known, _)| *known == name) {
+                Some(known) => known.1 = theme,
+                None => all_themes.push((name, theme)),
+            }
+        }
+        let painter = Painter::new(
+            theme_named(&all_themes, &settings.theme),
+            settings.human_mark,
+        );
+        let mut app = App {
+            setup,
+            bindings: settings.key_bindings(),
+            themes: all_themes,
+            painter,
+            stick: Stick::default(),
+            settings,
+            settings_path,
+            click: sound::tone(880.0, 40),
+            chime: sound::tone(660.0, 400),
+            scene: Scene::MainMenu,
+            selected: 0,
+            game: None,
+            statistics: None,
+            message: None,
+        };
+        if in_game {
+            app.game = Some(app.setup.build()?);
+            app.scene = Scene::InGame;
+        }
+        Ok(app)
+    }
+
+    pub(crate) fn scene(&self) -> Scene {
+        self.scene
+    }
+
+    pub(crate) fn game_mut(&mut self) -> Option<&mut Box<dyn Mode>> {
+        self.game.as_mut()
+    }
+
+    pub(crate) fn painter(&self) -> &Painter {
+        &self.painter
+    }
+
+    pub(crate) fn painter_mut(&mut self) -> &mut Painter {
+        &mut self.painter
+    }
+
+    /// The game along with the painter it is drawn with.
+    pub(crate) fn game_and_painter(&mut self) -> (Option<&mut Box<dyn Mode>>, &mut Painter) {
+        (self.game.as_mut(), &mut self.painter)
+    }
+
+    pub(crate) fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    pub(crate) fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub(crate) fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub(crate) fn bindings(&self) -> &KeyBindings {
+        &self.bindings
+    }
+
+    pub(crate) fn stick_mut(&mut self) -> &mut Stick {
+        &mut self.stick
+    }
+
+    /// Sound of picking a menu item.
+    pub(crate) fn click(&self) -> &SoundData {
+        &self.click
+    }
+
+    /// Sound of a game ending.
+    pub(crate) fn chime(&self) -> &SoundData {
+        &self.chime
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn show(&mut self, scene: Scene) {
+        self.scene = scene;
+        self.selected = 0;
+        self.message = None;
+    }
+
+    pub(crate) fn title(&self) -> String {
+        match self.scene {
+            Scene::MainMenu => String::from(GAME_NAME),
+            Scene::NewGame => String::from("New game"),
+            Scene::InGame => String::new(),
+            Scene::Pause => String::from("Paused"),
+            Scene::GameOver => self
+                .game
+                .as_ref()
+                .and_then(|game| game.result())
+                .unwrap_or_default(),
+            Scene::Settings => String::from("Settings"),
+            // The statistics carry their own heading.
+            Scene::Statistics => String::new(),
+        }
+    }
+
+    /// The menu of the scene with the label of each item.
+    pub(crate) fn items(&self) -> Vec<(Action, String)> {
+        let item = |action, label: &str| (action, String::from(label));
+        match self.scene {
+            Scene::MainMenu => {
+                let mut items = Vec::new();
+                if self
+                    .game
+                    .as_ref()
+                    .is_some_and(|game| game.result().is_none())
+                {
+                    items.push(item(Action::Continue, "Continue"));
+                }
+                items.push(item(Action::NewGame, "New game"));
+                items.push(item(Action::Settings, "Settings"));
+                items.push(item(Action::Statistics, "Statistics"));
+                items.push(item(Action::Quit, "Quit"));
+                items
+            }
+            Scene::NewGame => vec![
+                (Action::Variant, format!("Variant: {}", self.setup.variant)),
+                item(Action::Start, "Start"),
+                item(Action::Back, "Back"),
+            ],
+            Scene::InGame => Vec::new(),
+            Scene::Pause => vec![
+                item(Action::Resume, "Resume"),
+                item(Action::Restart, "Restart"),
+                item(Action::MainMenu, "Main menu"),
+            ],
+            Scene::GameOver => vec![
+                item(Action::Restart, "Play again"),
+                item(Action::MainMenu, "Main menu"),
+            ],
+            Scene::Settings => {
+                let settings = &self.settings;
+                let first = match settings.first {
+                    Player::Player1 => "computer",
+                    Player::Player2 => "you",
+                };
+                let mark = match settings.human_mark {
+                    Mark::Cross => "X",
+                    Mark::Nought => "O",
+                };
+                let animation = if settings.animation_speed == 0.0 {
+                    String::from("off")
+                } else {
+                    format!("{}x", settings.animation_speed)
+                };
+                vec![
+                    (
+                        Action::Difficulty,
+                        format!("Difficulty: {}", settings.difficulty),
+                    ),
+                    (Action::First, format!("Moves first: {}", first)),
+                    (Action::Mark, format!("Your mark: {}", mark)),
+                    (Action::Theme, format!("Theme: {}", settings.theme)),
+                    (Action::Animation, format!("Animations: {}", animation)),
+                    (Action::Volume, format!("Volume: {}%", settings.volume)),
+                    (Action::Keys, format!("Keys: {}", settings.keys)),
+                    item(Action::Back, "Back"),
+                ]
+            }
+            Scene::Statistics => vec![item(Action::Back, "Back")],
+        }
+    }
+
+    /// Moves the highlight `delta` items down, wrapping round.
+    pub(crate) fn move_selection(&mut self, delta: isize) {
+        let count = self.items().len() as isize;
+        if count > 0 {
+            self.selected = (self.selected as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    pub(crate) fn select(&mut self, index: usize) {
+        if index < self.items().len() {
+            self.selected = index;
+        }
+    }
+
+    /// Picks the highlighted item; `step` goes through the values of an
+    /// option backwards or forwards, and is 1 when the item is just picked.
+    pub(crate) fn activate(&mut self, step: isize) {
+        let action = match self.items().get(self.selected) {
+            Some(&(action, _)) => action,
+            None => return,
+        };
+        match action {
+            Action::Continue | Action::Resume => self.show(Scene::InGame),
+            Action::NewGame => self.show(Scene::NewGame),
+            Action::Settings => self.show(Scene::Settings),
+            Action::Statistics => self.show_statistics(),
+            Action::Quit => (),
+            Action::Variant => {
+                self.setup.variant = cycle(&Variant::ALL, self.setup.variant, step);
+                self.message = None;
+            }
+            Action::Difficulty
+            | Action::First
+            | Action::Mark
+            | Action::Theme
+            | Action::Animation
+            | Action::Volume
+            | Action::Keys => self.change_setting(action, step),
+            Action::Start => match self.setup.build() {
+                Ok(game) => {
+                    self.game = Some(game);
+                    self.show(Scene::InGame);
+                }
+                Err(e) => self.message = Some(e.to_string()),
+            },
+            Action::Restart => {
+                if let Some(game) = &mut self.game {
+                    game.restart();
+                }
+                self.show(Scene::InGame);
+            }
+            Action::MainMenu | Action::Back => self.show(Scene::MainMenu),
+        }
+    }
+
+    /// The highlighted item, which is what picking it does.
+    pub(crate) fn selected_action(&self) -> Option<Action> {
+        self.items().get(self.selected).map(|&(action, _)| action)
+    }
+
+    /// Escape: pauses a game, resumes a paused one and leaves other menus.
+    pub(crate) fn back(&mut self) {
+        match self.scene {
+            Scene::MainMenu => (),
+            Scene::InGame => self.show(Scene::Pause),
+            Scene::Pause => self.show(Scene::InGame),
+            _ => self.show(Scene::MainMenu),
+        }
+    }
+
+    /// Steps the setting of `action` on, applies it from the next game on
+    /// and saves it.
+    fn change_setting(&mut self, action: Action, step: isize) {
+        let settings = &mut self.settings;
+        match action {
+            Action::Difficulty => {
+                settings.difficulty = cycle(&Difficulty::ALL, settings.difficulty, step);
+                self.setup.difficulty = settings.difficulty;
+            }
+            Action::First => {
+                settings.first = settings.first.opponent();
+                self.setup.first = settings.first;
+            }
+            Action::Mark => {
+                settings.human_mark = match settings.human_mark {
+                    Mark::Cross => Mark::Nought,
+                    Mark::Nought => Mark::Cross,
+                };
+                self.painter.set_human_mark(settings.human_mark);
+            }
+            Action::Theme => {
+                let names: Vec<&str> = self.themes.iter().map(|(name, _)| name.as_str()).collect();
+                settings.theme = String::from(cycle(&names, settings.theme.as_str(), step));
+                self.painter
+                    .set_theme(theme_named(&self.themes, &settings.theme));
+            }
+            Action::Animation => {
+                settings.animation_speed = cycle(&ANIMATION_SPEEDS, settings.animation_speed, step);
+                self.setup.animation_speed = settings.animation_speed;
+            }
+            Action::Volume => settings.volume = cycle(&VOLUMES, settings.volume, step),
+            Action::Keys => {
+                settings.keys = cycle(&KeyPreset::ALL, settings.keys, step);
+                self.bindings = settings.key_bindings();
+            }
+            _ => return,
+        }
+        self.message = None;
+        if let Some(path) = &self.settings_path {
+            if let Err(e) = settings.save(path) {
+                self.message = Some(format!("Can't save settings: {}", e));
+            }
+        }
+    }
+
+    fn show_statistics(&mut self) {
+        self.show(Scene::Statistics);
+        self.statistics = None;
+        if let Some(path) = &self.setup.history {
+            match History::open(path.clone()) {
+                Ok(history) => self.statistics = Some(Statistics::from_entries(history.entries())),
+                Err(e) => self.message = Some(format!("Can't read game history: {}", e)),
+            }
+        }
+    }
+
+    /// Shows the game-over screen once the game in progress has ended.
+    pub(crate) fn check_game_over(&mut self) {
+        let over = self
+            .game
+            .as_ref()
+            .is_some_and(|game| game.result().is_some() && !game.is_animating());
+        if self.scene == Scene::InGame && over {
+            self.show(Scene::GameOver);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ttt::game::Cell;
+
+    #[test]
+    fn menus_lead_into_a_game_and_back() {
+        let mut app = App::new(
+            Setup::default(),
+            Settings::default(),
+            None,
+            Vec::new(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(app.scene(), Scene::MainMenu);
+        assert_eq!(app.selected_action(), Some(Action::NewGame));
+
+        app.activate(1);
+        assert_eq!(app.scene(), Scene::NewGame);
+        app.activate(-1);
+        assert_eq!(app.setup.variant, Variant::Puzzle);
+        app.activate(1);
+        app.move_selection(1);
+        app.activate(1);
+        assert_eq!(app.scene(), Scene::InGame);
+
+        app.back();
+        assert_eq!(app.scene(), Scene::Pause);
+        app.move_selection(-1);
+        app.activate(1);
+        assert_eq!(app.scene(), Scene::MainMenu);
+        assert_eq!(app.selected_action(), Some(Action::Continue));
+    }
+
+    #[test]
+    fn setup_checks_the_variant_options() {
+        let mut setup = Setup::default();
+        assert_eq!(setup.check(), Ok(()));
+        setup.topology = Topology::Torus;
+        setup.variant = Variant::Wild;
+        assert!(setup.check().is_err());
+        setup.topology = Topology::Flat;
+        setup.variant = Variant::Gravity;
+        setup.connect = Some(8);
+        assert!(setup.check().is_err());
+        setup.connect = None;
+        setup.variant = Variant::Vanishing;
+        setup.clock = "sudden:60".parse().ok();
+        assert!(setup.check().is_err());
+        assert_eq!("qubic".parse(), Ok(Variant::Qubic));
+    }
+
+    #[test]
+    fn restarting_a_match_game_resigns_it() {
+        let mut game = Game::new(Ai::new(TieBreak::First, 0.0), RuleSet::Standard);
+        game.set_series(3);
+        game.restart();
+        assert_eq!(game.series().unwrap().games_played(), 0);
+
+        game.set_cell_state(1, 1, Player::Player2);
+        game.restart();
+        let series = game.series().unwrap();
+        assert_eq!(series.wins(Player::Player1), 1);
+        // The computer opens the next game.
+        assert_eq!(series.next_first(), Player::Player1);
+        assert_eq!(
+            game.get_cells()
+                .iter()
+                .filter(|&&c| c != Cell::Empty)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn game_over_waits_for_the_animations() {
+        let mut app = App::new(
+            Setup::default(),
+            Settings::default(),
+            None,
+            Vec::new(),
+            false,
+        )
+        .unwrap();
+        app.activate(1);
+        app.move_selection(1);
+        app.activate(1);
+        let won = || {
+            let mut game = Game::new(Ai::new(TieBreak::First, 0.0), RuleSet::Standard);
+            for x in 0..3 {
+                game.set_cell_state(x, 0, Player::Player2);
+            }
+            game
+        };
+        assert!(won().is_animating());
+        app.game = Some(Box::new(won()));
+        app.check_game_over();
+        assert_eq!(app.scene(), Scene::InGame);
+
+        let mut game = won();
+        game.animate(std::time::Duration::from_secs(5));
+        assert!(!game.is_animating());
+        app.game = Some(Box::new(game));
+        app.check_game_over();
+        assert_eq!(app.scene(), Scene::GameOver);
+    }
+
+    #[test]
+    fn settings_apply_to_the_next_game() {
+        let mut app = App::new(
+            Setup::default(),
+            Settings::default(),
+            None,
+            Vec::new(),
+            false,
+        )
+        .unwrap();
+        app.move_selection(1);
+        app.activate(1);
+        assert_eq!(app.scene(), Scene::Settings);
+        app.activate(-1);
+        assert_eq!(app.settings().difficulty, Difficulty::Medium);
+        app.move_selection(1);
+        app.activate(1);
+        assert_eq!(app.setup.first, Player::Player1);
+        assert_eq!(app.setup.difficulty, Difficulty::Medium);
+        app.move_selection(2);
+        app.activate(1);
+        assert_eq!(app.settings().theme, "dark");
+        assert_eq!(app.painter().theme(), &Theme::DARK);
+    }
+}
@@ -0,0 +1,241 @@
+use ggez::{
+    audio::{self, SoundData, SoundSource},
+    event::{self, Axis, Button, GamepadId, KeyCode, KeyMods, MouseButton},
+    graphics::{self, DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::{Action, App, Scene};
+use super::config::{MENU_ITEM_SIZE, MENU_POS, SCREEN_SIZE, STATISTICS_POS};
+use super::keys;
+use super::stats::{cell_name, GameOutcome, Statistics};
+
+/// Where the first item of the scene's menu goes.
+fn menu_pos(scene: Scene) -> (f32, f32) {
+    match scene {
+        Scene::Statistics => (STATISTICS_POS.0, SCREEN_SIZE.1 - 2.0 * MENU_ITEM_SIZE.1),
+        _ => MENU_POS,
+    }
+}
+
+/// The menu item of `count` under the mouse.
+fn menu_item_at(scene: Scene, count: usize, x: f32, y: f32) -> Option<usize> {
+    let pos = menu_pos(scene);
+    if x < pos.0 || x > pos.0 + MENU_ITEM_SIZE.0 || y < pos.1 {
+        return None;
+    }
+    let index = ((y - pos.1) / MENU_ITEM_SIZE.1) as usize;
+    if index < count {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+/// Plays `sound` at the volume of the settings. A missing sound device
+/// only costs the sound.
+fn play(ctx: &mut Context, sound: &SoundData, volume: u8) {
+    if volume == 0 {
+        return;
+    }
+    if let Ok(mut source) = audio::Source::from_data(ctx, sound.clone()) {
+        source.set_volume(f32::from(volume) / 100.0);
+        let _ = source.play_detached();
+    }
+}
+
+impl App {
+    /// Picks the highlighted item; quitting needs the context.
+    fn pick(&mut self, ctx: &mut Context, step: isize) {
+        play(ctx, self.click(), self.settings().volume);
+        if self.selected_action() == Some(Action::Quit) {
+            event::quit(ctx);
+        } else {
+            self.activate(step);
+        }
+    }
+
+    /// Keys as the games know them, once the bindings have been applied.
+    fn handle_key(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, repeat: bool) {
+        if keycode == KeyCode::Escape {
+            self.back();
+            return;
+        }
+        if self.scene() == Scene::InGame {
+            if let Some(game) = self.game_mut() {
+                game.key_down_event(ctx, keycode, keymod, repeat);
+            }
+            self.game_event_done(ctx);
+            return;
+        }
+        let on_option = self.selected_action().is_some_and(Action::is_option);
+        match keycode {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Tab => self.move_selection(1),
+            KeyCode::Left if on_option => self.pick(ctx, -1),
+            KeyCode::Right if on_option => self.pick(ctx, 1),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => self.pick(ctx, 1),
+            _ => (),
+        }
+    }
+
+    /// After the game has handled an event: chimes if that ended it.
+    fn game_event_done(&mut self, ctx: &mut Context) {
+        self.check_game_over();
+        if self.scene() == Scene::GameOver {
+            play(ctx, self.chime(), self.settings().volume);
+        }
+    }
+}
+
+impl event::EventHandler for App {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        // Nothing runs behind a menu, clocks included.
+        if self.scene() == Scene::InGame {
+            if let Some(game) = self.game_mut() {
+                game.update(ctx)?;
+            }
+            self.game_event_done(ctx);
+        }
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if self.scene() == Scene::InGame {
+            if let Some(game) = self.game_mut() {
+                game.mouse_button_up_event(ctx, button, x, y);
+            }
+            self.game_event_done(ctx);
+        } else if let Some(index) = menu_item_at(self.scene(), self.items().len(), x, y) {
+            self.select(index);
+            match button {
+                MouseButton::Left => self.pick(ctx, 1),
+                MouseButton::Right if self.selected_action().is_some_and(Action::is_option) => {
+                    self.pick(ctx, -1)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        if self.scene() == Scene::InGame {
+            if let Some(game) = self.game_mut() {
+                game.mouse_motion_event(ctx, x, y, dx, dy);
+            }
+        } else if let Some(index) = menu_item_at(self.scene(), self.items().len(), x, y) {
+            self.select(index);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymod: KeyMods,
+        repeat: bool,
+    ) {
+        if let Some(keycode) = self.bindings().translate(keycode) {
+            self.handle_key(ctx, keycode, keymod, repeat);
+        }
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, _id: GamepadId) {
+        if let Some(command) = keys::gamepad_command(btn) {
+            self.handle_key(ctx, command.key(), KeyMods::default(), false);
+        }
+    }
+
+    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        if let Some(command) = self.stick_mut().moved(axis, value) {
+            self.handle_key(ctx, command.key(), KeyMods::default(), false);
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let scene = self.scene();
+        match scene {
+            Scene::InGame | Scene::Pause | Scene::GameOver => {
+                if let (Some(game), painter) = self.game_and_painter() {
+                    game.draw(ctx, painter)?;
+                }
+            }
+            _ => self.painter_mut().clear_background(ctx),
+        }
+
+        if scene != Scene::InGame {
+            if scene == Scene::Pause || scene == Scene::GameOver {
+                let mb = &mut MeshBuilder::new();
+                self.painter().draw_veil(mb);
+                let mbb = mb.build(ctx)?;
+                ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+            }
+            if scene == Scene::Statistics {
+                let statistics = match self.statistics() {
+                    Some(statistics) => statistics_to_str(statistics),
+                    None => String::from("Statistics\n\nNo game history is kept"),
+                };
+                self.painter()
+                    .draw_text_at(ctx, &statistics, STATISTICS_POS);
+            }
+            let labels: Vec<String> = self.items().into_iter().map(|(_, label)| label).collect();
+            let pos = menu_pos(scene);
+            self.painter()
+                .draw_menu(ctx, &self.title(), &labels, self.selected(), pos);
+            if let Some(message) = self.message() {
+                let below = pos.1 + MENU_ITEM_SIZE.1 * (labels.len() as f32 + 0.5);
+                self.painter().draw_text_at(ctx, message, (pos.0, below));
+            }
+        }
+
+        graphics::present(ctx)?;
+
+        ggez::timer::yield_now();
+        Ok(())
+    }
+}
+
+/// Results per difficulty, streaks and favourite openings.
+fn statistics_to_str(statistics: &Statistics) -> String {
+    let mut text =
+        String::from("Statistics of the classic board\nGames of the other variants aren't tracked");
+    if statistics.per_difficulty.is_empty() {
+        text += "\n\nNo games played yet";
+        return text;
+    }
+    for (difficulty, tally) in &statistics.per_difficulty {
+        text += &format!(
+            "\n{}: {} games, {} won ({:.0}%), {} drawn ({:.0}%), {} lost ({:.0}%)",
+            difficulty,
+            tally.games(),
+            tally.wins,
+            tally.percent(tally.wins),
+            tally.draws,
+            tally.percent(tally.draws),
+            tally.losses,
+            tally.percent(tally.losses)
+        );
+    }
+    if let Some((outcome, count)) = statistics.current_streak {
+        let outcome = match outcome {
+            GameOutcome::Win => "won",
+            GameOutcome::Draw => "drawn",
+            GameOutcome::Loss => "lost",
+        };
+        text += &format!("\n\nLast {} games {}", count, outcome);
+    }
+    text += &format!(
+        "\nLongest winning streak: {}",
+        statistics.longest_winning_streak
+    );
+    let openings: Vec<String> = statistics
+        .openings
+        .iter()
+        .take(3)
+        .map(|&(cell, count)| format!("{} ({})", cell_name(cell), count))
+        .collect();
+    if !openings.is_empty() {
+        text += &format!("\nMost common openings: {}", openings.join(", "));
+    }
+    text
+}
@@ -0,0 +1,17 @@
+use ggez::{GameError, GameResult};
+
+/// Value following `name` on the command line, e.g. `--games 100`.
+pub fn arg_value(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1).cloned())
+}
+
+pub fn parse_arg<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> GameResult<T> {
+    match arg_value(args, name) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| GameError::ConfigError(format!("bad value '{}' for {}", value, name))),
+        None => Ok(default),
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use super::config::{CLOCK_MAX_SECS, CLOCK_MOVES_TO_GO};
+use super::game::Player;
+
+/// How much time the players get.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimeControl {
+    /// The whole game has to be played within the time.
+    SuddenDeath(Duration),
+    /// Starting time, plus `increment` after every move.
+    Increment { base: Duration, increment: Duration },
+    /// Every move has to be made within the time; none carries over.
+    PerMove(Duration),
+}
+
+impl std::str::FromStr for TimeControl {
+    type Err = String;
+
+    /// `sudden:<seconds>`, `increment:<seconds>+<seconds>` or `move:<seconds>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || format!("bad time control '{}'", s);
+        let seconds = |value: &str| {
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|&seconds| seconds > 0.0 && seconds.is_finite())
+                .map(|seconds| Duration::from_secs_f32(seconds.min(CLOCK_MAX_SECS)))
+                .ok_or_else(bad)
+        };
+        let (kind, value) = s.split_once(':').ok_or_else(bad)?;
+        match kind {
+            "sudden" => Ok(TimeControl::SuddenDeath(seconds(value)?)),
+            "increment" => {
+                let (base, increment) = value.split_once('+').ok_or_else(bad)?;
+                Ok(TimeControl::Increment {
+                    base: seconds(base)?,
+                    increment: seconds(increment)?,
+                })
+            }
+            "move" => Ok(TimeControl::PerMove(seconds(value)?)),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl TimeControl {
+    fn initial(self) -> Duration {
+        match self {
+            TimeControl::SuddenDeath(time) | TimeControl::PerMove(time) => time,
+            TimeControl::Increment { base, .. } => base,
+        }
+    }
+}
+
+/// A clock for each player; only the one whose turn it is runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clock {
+    control: TimeControl,
+    /// Time left for `Player1` and `Player2`.
+    remaining: [Duration; 2],
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        Clock {
+            control,
+            remaining: [control.initial(); 2],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Clock::new(self.control);
+    }
+
+    pub fn remaining(&self, player: Player) -> Duration {
+        self.remaining[player.index()]
+    }
+
+    /// Runs the clock of `player` for `elapsed`; true once their time is up.
+    pub fn tick(&mut self, player: Player, elapsed: Duration) -> bool {
+        let remaining = &mut self.remaining[player.index()];
+        *remaining = remaining.saturating_sub(elapsed);
+        *remaining == Duration::ZERO
+    }
+
+    /// Called when `player` has moved, to hand out their time for the next move.
+    pub fn finish_move(&mut self, player: Player) {
+        let remaining = &mut self.remaining[player.index()];
+        match self.control {
+            TimeControl::SuddenDeath(_) => (),
+            TimeControl::Increment { increment, .. } => *remaining += increment,
+            TimeControl::PerMove(time) => *remaining = time,
+        }
+    }
+
+    /// How long `player` should think about the next move: an even share of
+    /// the time left over `CLOCK_MOVES_TO_GO` moves, and most of an increment
+    /// or of the time per move, which don't need saving up.
+    pub fn budget(&self, player: Player) -> Duration {
+        let remaining = self.remaining(player);
+        match self.control {
+            TimeControl::SuddenDeath(_) => remaining / CLOCK_MOVES_TO_GO,
+            TimeControl::Increment { increment, .. } => {
+                (remaining / CLOCK_MOVES_TO_GO + increment * 3 / 4).min(remaining / 2)
+            }
+            TimeControl::PerMove(_) => remaining / 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_controls_hand_out_time() {
+        let control: TimeControl = "increment:60+2".parse().unwrap();
+        let mut clock = Clock::new(control);
+        assert!(!clock.tick(Player::Player2, Duration::from_secs(10)));
+        clock.finish_move(Player::Player2);
+        assert_eq!(clock.remaining(Player::Player2), Duration::from_secs(52));
+        assert_eq!(clock.remaining(Player::Player1), Duration::from_secs(60));
+
+        let mut clock = Clock::new("move:5".parse().unwrap());
+        assert!(clock.tick(Player::Player1, Duration::from_secs(7)));
+        clock.finish_move(Player::Player1);
+        assert_eq!(clock.remaining(Player::Player1), Duration::from_secs(5));
+
+        assert!("sudden:-1".parse::<TimeControl>().is_err());
+        assert!("sudden:inf".parse::<TimeControl>().is_err());
+        assert!("sudden:NaN".parse::<TimeControl>().is_err());
+        assert_eq!(
+            "sudden:1e30".parse(),
+            Ok(TimeControl::SuddenDeath(Duration::from_secs_f32(
+                CLOCK_MAX_SECS
+            )))
+        );
+        assert!("blitz:60".parse::<TimeControl>().is_err());
+    }
+}
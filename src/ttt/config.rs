@@ -1,6 +1,14 @@
+use super::game_logic::{Difficulty, TieBreak};
+
 pub static GAME_NAME: &str = "TicTacToe";
 pub static AUTHOR: &str = "Alexander Ovchinnikov";
 
+pub static AI_TIE_BREAK: TieBreak = TieBreak::CenterFirst;
+
+pub static AI_BOOK_VARIETY: f32 = 1.0;
+
+pub static AI_DIFFICULTY: Difficulty = Difficulty::Hard;
+
 pub static SCREEN_SIZE: (f32, f32) = (960.0, 640.0);
 
 pub static SQUARE_SIZE: f32 = 150.0;
@@ -11,3 +19,111 @@ pub static PLAY_FIELD_POS: (f32, f32) = (
     SCREEN_SIZE.0 / 2.0 - SQUARE_SIZE * (PLAY_FIELD_SIZE as f32 / 2.0),
     SCREEN_SIZE.1 / 2.0 - SQUARE_SIZE * (PLAY_FIELD_SIZE as f32 / 2.0),
 );
+
+pub static ULTIMATE_SQUARE_SIZE: f32 = 55.0;
+
+pub static ULTIMATE_FIELD_POS: (f32, f32) = (
+    SCREEN_SIZE.0 / 2.0 - ULTIMATE_SQUARE_SIZE * 4.5,
+    SCREEN_SIZE.1 / 2.0 - ULTIMATE_SQUARE_SIZE * 4.5,
+);
+
+pub static ULTIMATE_AI_TIME_MS: u64 = 1000;
+
+pub static QUBIC_SQUARE_SIZE: f32 = 50.0;
+
+pub static QUBIC_LAYER_GAP: f32 = 30.0;
+
+pub static QUBIC_FIELD_POS: (f32, f32) = (
+    SCREEN_SIZE.0 / 2.0 - QUBIC_SQUARE_SIZE * 8.0 - QUBIC_LAYER_GAP * 1.5,
+    SCREEN_SIZE.1 / 2.0 - QUBIC_SQUARE_SIZE * 2.0,
+);
+
+pub static QUBIC_AI_TIME_MS: u64 = 1000;
+
+pub static GRAVITY_BOARD_SIZE: (usize, usize) = (7, 6);
+
+pub static GRAVITY_CONNECT: usize = 4;
+
+/// Largest square size; bigger boards shrink to fit the window.
+pub static GRAVITY_SQUARE_SIZE: f32 = 80.0;
+
+/// How fast a dropped piece falls, in cells per second.
+pub static GRAVITY_DROP_SPEED: f32 = 12.0;
+
+pub static GRAVITY_AI_TIME_MS: u64 = 1000;
+
+/// Plies the computer looks ahead with vanishing marks.
+pub static VANISHING_AI_DEPTH: usize = 10;
+
+pub static NUMERICAL_AI_ITERATIONS: usize = 5000;
+
+/// Squares of the number picker next to the board.
+pub static NUMERICAL_PICKER_SIZE: f32 = 60.0;
+
+pub static NUMERICAL_PICKER_POS: (f32, f32) = (
+    PLAY_FIELD_POS.0 + SQUARE_SIZE * PLAY_FIELD_SIZE as f32 + 60.0,
+    PLAY_FIELD_POS.1,
+);
+
+pub static MULTI_BOARD_SIZE: (usize, usize) = (6, 6);
+
+pub static MULTI_CONNECT: usize = 3;
+
+pub static MULTI_PLAYERS: usize = 3;
+
+/// Largest square size; bigger boards shrink to fit the window.
+pub static MULTI_SQUARE_SIZE: f32 = 80.0;
+
+/// Plies of paranoid search for each computer player.
+pub static MULTI_AI_DEPTH: usize = 3;
+
+/// Moves a player is expected to still make when sharing out clock time;
+/// nobody makes more than five on the classic board.
+pub static CLOCK_MOVES_TO_GO: u32 = 5;
+
+/// Longest time a clock accepts, in seconds; anything above is cut down to it.
+pub static CLOCK_MAX_SECS: f32 = 24.0 * 60.0 * 60.0;
+
+/// Where the clocks are shown, left of the board.
+pub static CLOCK_POS: (f32, f32) = (PLAY_FIELD_POS.0 - 180.0, PLAY_FIELD_POS.1);
+
+/// Games history, kept in `rust-tictactoe` under the XDG data directory.
+pub static HISTORY_FILE: &str = "history.tsv";
+
+/// Settings, kept in `rust-tictactoe` under the XDG config directory.
+pub static SETTINGS_FILE: &str = "settings.conf";
+
+/// Theme files, `<name>.theme` in this directory under the settings'.
+pub static THEMES_DIR: &str = "themes";
+
+/// The theme to draw with until the settings say otherwise.
+pub static DEFAULT_THEME: &str = "classic";
+
+/// How fast animations run until the settings say otherwise.
+pub static ANIMATION_SPEED: f32 = 1.0;
+
+/// Seconds it takes to draw a mark at animation speed 1.
+pub static MARK_DRAW_SECS: f32 = 0.25;
+
+/// Seconds the winning line takes to sweep across the board.
+pub static WIN_LINE_SECS: f32 = 0.4;
+
+/// Seconds the marks of the last game take to fade out on a restart.
+pub static BOARD_CLEAR_SECS: f32 = 0.3;
+
+/// Sound volume in percent until the settings say otherwise.
+pub static VOLUME: u8 = 50;
+
+/// How far a gamepad stick has to be pushed to move the cursor, out of 1.
+pub static GAMEPAD_STICK_THRESHOLD: f32 = 0.6;
+
+/// Where the statistics screen starts.
+pub static STATISTICS_POS: (f32, f32) = (60.0, 60.0);
+
+/// Top left corner of the first menu item; the menu title goes above it.
+pub static MENU_POS: (f32, f32) = (SCREEN_SIZE.0 / 2.0 - MENU_ITEM_SIZE.0 / 2.0, 220.0);
+
+/// Width and height of a menu item.
+pub static MENU_ITEM_SIZE: (f32, f32) = (360.0, 48.0);
+
+pub static MENU_FONT_SIZE: f32 = 32.0;
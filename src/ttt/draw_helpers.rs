@@ -1,179 +1,785 @@
 use ggez::{
-    graphics::{self, MeshBuilder, Text},
+    graphics::{self, Color, MeshBuilder, Scale, Text, TextFragment},
     nalgebra::Point2,
-    Context,
+    Context, GameResult,
 };
 
-use super::config::{PLAY_FIELD_POS, PLAY_FIELD_SIZE, SCREEN_SIZE, SQUARE_SIZE};
-use super::game::Player;
+use super::config::{
+    MENU_FONT_SIZE, MENU_ITEM_SIZE, PLAY_FIELD_POS, PLAY_FIELD_SIZE, SCREEN_SIZE, SQUARE_SIZE,
+};
+use super::game::{Mark, Player, Topology};
+use super::skin::Skin;
+use super::theme::{PieceStyle, Theme};
+
+/// A `0xRRGGBB` theme colour.
+pub(crate) fn rgb(color: u32) -> Color {
+    Color::from_rgb_u32(color)
+}
+
+/// What the boards and menus are drawn with: the theme and the mark the
+/// human picked in the settings, and the theme's images. The app keeps one
+/// and hands it to the game it draws.
+pub(crate) struct Painter {
+    theme: Theme,
+    human_mark: Mark,
+    skin: Skin,
+}
+
+impl Painter {
+    pub(crate) fn new(theme: Theme, human_mark: Mark) -> Self {
+        Painter {
+            theme,
+            human_mark,
+            skin: Skin::default(),
+        }
+    }
+
+    pub(crate) fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// The images of `theme` are loaded the next time a frame starts.
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub(crate) fn set_human_mark(&mut self, mark: Mark) {
+        self.human_mark = mark;
+    }
+
+    /// Starts a frame with the theme's background colour and board image.
+    pub(crate) fn clear_background(&mut self, ctx: &mut Context) {
+        graphics::clear(ctx, rgb(self.theme.background));
+        self.skin.draw_board(ctx, &self.theme);
+    }
+
+    /// See `Skin::draw_sprites`.
+    pub(crate) fn draw_sprites(&mut self, ctx: &mut Context) -> GameResult {
+        self.skin.draw_sprites(ctx)
+    }
 
-pub(crate) fn draw_field(mb: &mut MeshBuilder) {
-    for i in 0..PLAY_FIELD_SIZE + 1 {
+    /// Classic grid with the `blocked` cells filled in.
+    pub(crate) fn draw_field(&self, mb: &mut MeshBuilder, blocked: &[usize]) {
+        let blocked_color = rgb(self.theme.blocked);
+        for &id in blocked {
+            let _ = mb.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(
+                    PLAY_FIELD_POS.0 + SQUARE_SIZE * (id % PLAY_FIELD_SIZE) as f32,
+                    PLAY_FIELD_POS.1 + SQUARE_SIZE * (id / PLAY_FIELD_SIZE) as f32,
+                    SQUARE_SIZE,
+                    SQUARE_SIZE,
+                ),
+                blocked_color,
+            );
+        }
+        draw_grid_lines(
+            mb,
+            PLAY_FIELD_POS,
+            SQUARE_SIZE,
+            (PLAY_FIELD_SIZE, PLAY_FIELD_SIZE),
+            (self.theme.grid_width, rgb(self.theme.grid)),
+        );
+    }
+
+    /// Grid of `size` x `size` squares with its top left corner at `pos`.
+    pub(crate) fn draw_grid(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        size: usize,
+        width: f32,
+    ) {
+        self.draw_board_grid(mb, pos, square_size, (size, size), width);
+    }
+
+    /// Grid of `columns` x `rows` squares with its top left corner at `pos`.
+    /// `width` is what the classic theme draws it with, other themes scale
+    /// it as they scale the classic board's grid.
+    pub(crate) fn draw_board_grid(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        size: (usize, usize),
+        width: f32,
+    ) {
+        let width = width * self.theme.grid_width / Theme::CLASSIC.grid_width;
+        draw_grid_lines(mb, pos, square_size, size, (width, rgb(self.theme.grid)));
+    }
+
+    pub(crate) fn draw_selected_cell(&self, mb: &mut MeshBuilder, index_x: usize, index_y: usize) {
+        let cursor_color = rgb(self.theme.cursor);
+        draw_frame(
+            mb,
+            PLAY_FIELD_POS,
+            SQUARE_SIZE,
+            index_x,
+            index_y,
+            cursor_color,
+        );
+    }
+
+    /// Line through the winning `cells` of the classic board, swept across
+    /// as far as `progress` from 0 to 1 says. On a torus a line that wraps
+    /// around leaves the board on one side and comes back in on the other;
+    /// on a flat board it joins the cells in order, which also covers lines
+    /// of custom shapes that don't run straight.
+    pub(crate) fn draw_red_line(
+        &self,
+        mb: &mut MeshBuilder,
+        cells: &[usize],
+        topology: Topology,
+        progress: f32,
+    ) {
+        let (width, color) = self.win_line_style();
+        let center = |id| cell_center(PLAY_FIELD_POS, SQUARE_SIZE, PLAY_FIELD_SIZE, id);
+        if topology == Topology::Flat {
+            let covered = progress * (cells.len() - 1) as f32;
+            for (i, pair) in cells.windows(2).enumerate() {
+                let part = covered - i as f32;
+                draw_line_part(mb, center(pair[0]), center(pair[1]), part, width, color);
+            }
+            return;
+        }
+
+        let size = PLAY_FIELD_SIZE as isize;
+        let step = |from: isize, to: isize| match (to - from).rem_euclid(size) {
+            0 => 0.0,
+            1 => 1.0,
+            _ => -1.0,
+        };
+        let (x0, y0) = (cells[0] as isize % size, cells[0] as isize / size);
+        let (x1, y1) = (cells[1] as isize % size, cells[1] as isize / size);
+        let (dx, dy) = (step(x0, x1), step(y0, y1));
+
+        let covered = progress * cells.len() as f32;
+        for (i, &id) in cells.iter().enumerate() {
+            let center = center(id);
+            // Half a square towards the previous and the next cell of the line.
+            let back = if i > 0 { 0.5 } else { 0.0 };
+            let ahead = if i + 1 < cells.len() { 0.5 } else { 0.0 };
+            draw_line_part(
+                mb,
+                Point2::new(
+                    center.x - dx * back * SQUARE_SIZE,
+                    center.y - dy * back * SQUARE_SIZE,
+                ),
+                Point2::new(
+                    center.x + dx * ahead * SQUARE_SIZE,
+                    center.y + dy * ahead * SQUARE_SIZE,
+                ),
+                covered - i as f32,
+                width,
+                color,
+            );
+        }
+    }
+
+    fn win_line_style(&self) -> (f32, Color) {
+        (self.theme.win_line_width, rgb(self.theme.win_line))
+    }
+
+    /// Winning line through the centers of two cells of a grid `size`
+    /// squares wide.
+    pub(crate) fn draw_red_line_at(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        size: usize,
+        index_first: usize,
+        index_second: usize,
+    ) {
+        let (width, color) = self.win_line_style();
         let _ = mb.line(
             &[
-                Point2::new(PLAY_FIELD_POS.0 + SQUARE_SIZE * i as f32, PLAY_FIELD_POS.1),
+                cell_center(pos, square_size, size, index_first),
+                cell_center(pos, square_size, size, index_second),
+            ],
+            width,
+            color,
+        );
+    }
+
+    pub(crate) fn draw_player1(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        pos_x: usize,
+        pos_y: usize,
+        color: Color,
+    ) {
+        self.draw_nought(mb, pos, square_size, (pos_x, pos_y), color, 1.0);
+    }
+
+    /// A nought drawn clockwise from the top as far as `progress` from 0 to
+    /// 1 says; a filled one grows instead.
+    fn draw_nought(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        (pos_x, pos_y): (usize, usize),
+        color: Color,
+        progress: f32,
+    ) {
+        if progress <= 0.0 {
+            return;
+        }
+        let center = Point2::new(
+            pos.0 + (pos_x as f32 + 0.5) * square_size,
+            pos.1 + (pos_y as f32 + 0.5) * square_size,
+        );
+        let radius = square_size / 4.0;
+        let width = self.theme.mark_width;
+        match self.theme.pieces {
+            PieceStyle::Outline if progress < 1.0 => {
+                let segments = (48.0 * progress).ceil() as usize;
+                let points: Vec<Point2<f32>> = (0..=segments)
+                    .map(|i| {
+                        let angle = std::f32::consts::TAU * progress * i as f32 / segments as f32
+                            - std::f32::consts::FRAC_PI_2;
+                        Point2::new(
+                            center.x + radius * angle.cos(),
+                            center.y + radius * angle.sin(),
+                        )
+                    })
+                    .collect();
+                let _ = mb.polyline(graphics::DrawMode::stroke(width), &points, color);
+            }
+            PieceStyle::Outline => {
+                mb.circle(
+                    graphics::DrawMode::stroke(width),
+                    center,
+                    radius,
+                    0.00001,
+                    color,
+                );
+            }
+            PieceStyle::Filled => {
+                mb.circle(
+                    graphics::DrawMode::fill(),
+                    center,
+                    radius * progress.min(1.0),
+                    0.00001,
+                    color,
+                );
+            }
+        }
+    }
+
+    pub(crate) fn draw_player2(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        pos_x: usize,
+        pos_y: usize,
+        color: Color,
+    ) {
+        self.draw_cross(mb, pos, square_size, (pos_x, pos_y), color, 1.0);
+    }
+
+    /// A cross drawn stroke by stroke as far as `progress` from 0 to 1 says.
+    fn draw_cross(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        (pos_x, pos_y): (usize, usize),
+        color: Color,
+        progress: f32,
+    ) {
+        let width = match self.theme.pieces {
+            PieceStyle::Outline => self.theme.mark_width,
+            PieceStyle::Filled => self.theme.mark_width * 2.0,
+        };
+        let corner = |x: f32, y: f32| {
+            Point2::new(
+                pos.0 + square_size * (pos_x as f32 + x),
+                pos.1 + square_size * (pos_y as f32 + y),
+            )
+        };
+        draw_line_part(
+            mb,
+            corner(0.25, 0.25),
+            corner(0.75, 0.75),
+            progress * 2.0,
+            width,
+            color,
+        );
+        draw_line_part(
+            mb,
+            corner(0.75, 0.25),
+            corner(0.25, 0.75),
+            progress * 2.0 - 1.0,
+            width,
+            color,
+        );
+    }
+
+    /// The mark drawn for `player`, after any swap the human asked for.
+    fn player_mark(&self, player: Player) -> Mark {
+        if self.human_mark == Mark::Nought {
+            player.opponent().mark()
+        } else {
+            player.mark()
+        }
+    }
+
+    /// `player`'s mark on the classic board, drawn as far as `progress` from
+    /// 0 to 1 says.
+    pub(crate) fn draw_player(
+        &mut self,
+        mb: &mut MeshBuilder,
+        player: Player,
+        pos_x: usize,
+        pos_y: usize,
+        progress: f32,
+    ) {
+        self.draw_player_in(
+            mb,
+            PLAY_FIELD_POS,
+            SQUARE_SIZE,
+            player,
+            (pos_x, pos_y),
+            (progress, 1.0),
+        );
+    }
+
+    pub(crate) fn draw_player_at(
+        &mut self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        player: Player,
+        pos_x: usize,
+        pos_y: usize,
+    ) {
+        self.draw_player_in(mb, pos, square_size, player, (pos_x, pos_y), (1.0, 1.0));
+    }
+
+    /// A mark of the board just cleared, fading out as `fade` goes from 0 to
+    /// 1.
+    pub(crate) fn draw_clearing_mark(
+        &mut self,
+        mb: &mut MeshBuilder,
+        player: Player,
+        pos_x: usize,
+        pos_y: usize,
+        fade: f32,
+    ) {
+        self.draw_player_in(
+            mb,
+            PLAY_FIELD_POS,
+            SQUARE_SIZE,
+            player,
+            (pos_x, pos_y),
+            (1.0, 1.0 - fade),
+        );
+    }
+
+    /// Images of marks fade in rather than being drawn stroke by stroke.
+    fn draw_player_in(
+        &mut self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        player: Player,
+        (pos_x, pos_y): (usize, usize),
+        (progress, alpha): (f32, f32),
+    ) {
+        if progress <= 0.0 || alpha <= 0.0 {
+            return;
+        }
+        let mark = self.player_mark(player);
+        let rect = mark_rect(pos, square_size, pos_x, pos_y);
+        let tint = Color::new(1.0, 1.0, 1.0, progress.min(1.0) * alpha);
+        if self.skin.queue_mark(mark, rect, tint) {
+            return;
+        }
+        let mut color = match player {
+            Player::Player1 => rgb(self.theme.player1),
+            Player::Player2 => rgb(self.theme.player2),
+        };
+        color.a = alpha;
+        self.draw_mark_colored(
+            mb,
+            pos,
+            square_size,
+            mark,
+            (pos_x, pos_y),
+            (color, progress),
+        );
+    }
+
+    /// A mark nobody owns, as in wild tic-tac-toe.
+    pub(crate) fn draw_mark_at(
+        &mut self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        mark: Mark,
+        pos_x: usize,
+        pos_y: usize,
+    ) {
+        let rect = mark_rect(pos, square_size, pos_x, pos_y);
+        if self.skin.queue_mark(mark, rect, graphics::WHITE) {
+            return;
+        }
+        let color = rgb(self.theme.text);
+        self.draw_mark_colored(mb, pos, square_size, mark, (pos_x, pos_y), (color, 1.0));
+    }
+
+    /// A mark about to be removed, drawn faded.
+    pub(crate) fn draw_oldest_mark(
+        &mut self,
+        mb: &mut MeshBuilder,
+        player: Player,
+        pos_x: usize,
+        pos_y: usize,
+    ) {
+        let mark = self.player_mark(player);
+        let rect = mark_rect(PLAY_FIELD_POS, SQUARE_SIZE, pos_x, pos_y);
+        if self
+            .skin
+            .queue_mark(mark, rect, Color::new(1.0, 1.0, 1.0, 0.5))
+        {
+            return;
+        }
+        let faded_color = rgb(self.theme.faded);
+        self.draw_mark_colored(
+            mb,
+            PLAY_FIELD_POS,
+            SQUARE_SIZE,
+            mark,
+            (pos_x, pos_y),
+            (faded_color, 1.0),
+        );
+    }
+
+    /// `mark` in `color`, drawn as far as `progress` from 0 to 1 says.
+    fn draw_mark_colored(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        mark: Mark,
+        cell: (usize, usize),
+        (color, progress): (Color, f32),
+    ) {
+        match mark {
+            Mark::Nought => self.draw_nought(mb, pos, square_size, cell, color, progress),
+            Mark::Cross => self.draw_cross(mb, pos, square_size, cell, color, progress),
+        }
+    }
+
+    /// Colour of each seat in games with more than two players.
+    pub(crate) fn seat_color(&self, seat: usize) -> Color {
+        rgb(self.theme.seats[seat.min(self.theme.seats.len() - 1)])
+    }
+
+    /// Symbol of `seat`: a cross, a circle, a triangle or a square.
+    pub(crate) fn draw_seat(
+        &self,
+        mb: &mut MeshBuilder,
+        pos: (f32, f32),
+        square_size: f32,
+        seat: usize,
+        pos_x: usize,
+        pos_y: usize,
+    ) {
+        let color = self.seat_color(seat);
+        let left = pos.0 + square_size * (pos_x as f32 + 0.25);
+        let top = pos.1 + square_size * (pos_y as f32 + 0.25);
+        let side = square_size / 2.0;
+        match seat {
+            0 => self.draw_player2(mb, pos, square_size, pos_x, pos_y, color),
+            1 => self.draw_player1(mb, pos, square_size, pos_x, pos_y, color),
+            2 => {
+                let _ = mb.polygon(
+                    graphics::DrawMode::stroke(4.0),
+                    &[
+                        Point2::new(left + side / 2.0, top),
+                        Point2::new(left + side, top + side),
+                        Point2::new(left, top + side),
+                    ],
+                    color,
+                );
+            }
+            _ => {
+                mb.rectangle(
+                    graphics::DrawMode::stroke(4.0),
+                    graphics::Rect::new(left, top, side, side),
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Plain text with its top left corner at `pos`.
+    pub(crate) fn draw_text_at(&self, ctx: &mut Context, text: &str, pos: (f32, f32)) {
+        let text = Text::new(text);
+        let color = rgb(self.theme.text);
+        let _ = graphics::draw(ctx, &text, (Point2::new(pos.0, pos.1), color));
+    }
+
+    pub(crate) fn draw_text(&self, ctx: &mut Context, text: &str) {
+        let fps_display = Text::new(format!(
+            "Game: {}\nPress 'R' to restart, Esc for the menu",
+            text
+        ));
+        let color = rgb(self.theme.text);
+        // When drawing through these calls, `DrawParam` will work as they are documented.
+        let _ = graphics::draw(
+            ctx,
+            &fps_display,
+            (Point2::new(0.0, SCREEN_SIZE.1 * 0.9), color),
+        );
+    }
+
+    /// Menu `title` over its `items`, the first one with its top left corner
+    /// at `pos` and the `selected` one in the cursor colour.
+    pub(crate) fn draw_menu(
+        &self,
+        ctx: &mut Context,
+        title: &str,
+        items: &[String],
+        selected: usize,
+        pos: (f32, f32),
+    ) {
+        let text =
+            |text: &str| Text::new(TextFragment::new(text).scale(Scale::uniform(MENU_FONT_SIZE)));
+        let (text_color, selected_color) = (rgb(self.theme.text), rgb(self.theme.cursor));
+        let title = text(title);
+        let (_, title_height) = title.dimensions(ctx);
+        let _ = graphics::draw(
+            ctx,
+            &title,
+            (
+                Point2::new(pos.0, pos.1 - title_height as f32 - MENU_ITEM_SIZE.1),
+                text_color,
+            ),
+        );
+        for (i, item) in items.iter().enumerate() {
+            let color = if i == selected {
+                selected_color
+            } else {
+                text_color
+            };
+            let item_pos = Point2::new(pos.0, pos.1 + MENU_ITEM_SIZE.1 * i as f32);
+            let _ = graphics::draw(ctx, &text(item), (item_pos, color));
+        }
+    }
+
+    /// Cover of the background colour over the whole screen, for a menu over
+    /// a game.
+    pub(crate) fn draw_veil(&self, mb: &mut MeshBuilder) {
+        let mut color = rgb(self.theme.background);
+        color.a = 0.85;
+        let _ = mb.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1),
+            color,
+        );
+    }
+}
+
+/// Grid of `columns` x `rows` squares with its top left corner at `pos`,
+/// its lines `width` wide.
+fn draw_grid_lines(
+    mb: &mut MeshBuilder,
+    pos: (f32, f32),
+    square_size: f32,
+    (columns, rows): (usize, usize),
+    (width, color): (f32, Color),
+) {
+    for i in 0..columns + 1 {
+        let _ = mb.line(
+            &[
+                Point2::new(pos.0 + square_size * i as f32, pos.1),
                 Point2::new(
-                    PLAY_FIELD_POS.0 + SQUARE_SIZE * i as f32,
-                    PLAY_FIELD_POS.1 + SQUARE_SIZE * PLAY_FIELD_SIZE as f32,
+                    pos.0 + square_size * i as f32,
+                    pos.1 + square_size * rows as f32,
                 ),
             ],
-            4.0,
-            graphics::BLACK,
+            width,
+            color,
         );
     }
-    for i in 0..PLAY_FIELD_SIZE + 1 {
+    for i in 0..rows + 1 {
         let _ = mb.line(
             &[
-                Point2::new(PLAY_FIELD_POS.0, PLAY_FIELD_POS.1 + SQUARE_SIZE * i as f32),
+                Point2::new(pos.0, pos.1 + square_size * i as f32),
                 Point2::new(
-                    PLAY_FIELD_POS.0 + SQUARE_SIZE * PLAY_FIELD_SIZE as f32,
-                    PLAY_FIELD_POS.1 + SQUARE_SIZE * i as f32,
+                    pos.0 + square_size * columns as f32,
+                    pos.1 + square_size * i as f32,
                 ),
             ],
-            4.0,
-            graphics::BLACK,
+            width,
+            color,
         );
     }
 }
 
-pub(crate) fn draw_selected_cell(mb: &mut MeshBuilder, index_x: usize, index_y: usize) {
-    let red_color = graphics::Color::from_rgb_u32(0x00FF0000);
+/// Outline just inside the square at (`index_x`, `index_y`).
+pub(crate) fn draw_frame(
+    mb: &mut MeshBuilder,
+    pos: (f32, f32),
+    square_size: f32,
+    index_x: usize,
+    index_y: usize,
+    color: Color,
+) {
     let offset = 5.0;
     let width = 5.0;
     let _ = mb.line(
         &[
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * index_x as f32 + offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * index_y as f32 + offset - 1.0,
+                pos.0 + square_size * index_x as f32 + offset,
+                pos.1 + square_size * index_y as f32 + offset - 1.0,
             ),
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (index_x + 1) as f32 - offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * index_y as f32 + offset - 1.0,
+                pos.0 + square_size * (index_x + 1) as f32 - offset,
+                pos.1 + square_size * index_y as f32 + offset - 1.0,
             ),
         ],
         width,
-        red_color,
+        color,
     );
     let _ = mb.line(
         &[
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * index_x as f32 + offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (index_y + 1) as f32 - offset,
+                pos.0 + square_size * index_x as f32 + offset,
+                pos.1 + square_size * (index_y + 1) as f32 - offset,
             ),
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (index_x + 1) as f32 - offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (index_y + 1) as f32 - offset,
+                pos.0 + square_size * (index_x + 1) as f32 - offset,
+                pos.1 + square_size * (index_y + 1) as f32 - offset,
             ),
         ],
         width,
-        red_color,
+        color,
     );
     let _ = mb.line(
         &[
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * index_x as f32 + offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * index_y as f32 + offset,
+                pos.0 + square_size * index_x as f32 + offset,
+                pos.1 + square_size * index_y as f32 + offset,
             ),
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * index_x as f32 + offset,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (index_y + 1) as f32 - offset,
+                pos.0 + square_size * index_x as f32 + offset,
+                pos.1 + square_size * (index_y + 1) as f32 - offset,
             ),
         ],
         width,
-        red_color,
+        color,
     );
     let _ = mb.line(
         &[
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (index_x + 1) as f32 - offset + 1.0,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * index_y as f32 + offset,
+                pos.0 + square_size * (index_x + 1) as f32 - offset + 1.0,
+                pos.1 + square_size * index_y as f32 + offset,
             ),
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (index_x + 1) as f32 - offset + 1.0,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (index_y + 1) as f32 - offset,
+                pos.0 + square_size * (index_x + 1) as f32 - offset + 1.0,
+                pos.1 + square_size * (index_y + 1) as f32 - offset,
             ),
         ],
         width,
-        red_color,
+        color,
     );
 }
-pub(crate) fn draw_red_line(mb: &mut MeshBuilder, index_first: usize, index_second: usize) {
-    let (point1_y, point1_x) = (index_first / 3, index_first % 3);
-    let (point2_y, point2_x) = (index_second / 3, index_second % 3);
-    let red_color = graphics::Color::from_rgb_u32(0x00FF0000);
-    let _ = mb.line(
-        &[
-            Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * point1_x as f32 + SQUARE_SIZE / 2.0,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * point1_y as f32 + SQUARE_SIZE / 2.0,
-            ),
-            Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * point2_x as f32 + SQUARE_SIZE / 2.0,
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * point2_y as f32 + SQUARE_SIZE / 2.0,
-            ),
-        ],
-        10.0,
-        red_color,
-    );
+
+/// Center of cell `id` of a grid `size` squares wide.
+fn cell_center(pos: (f32, f32), square_size: f32, size: usize, id: usize) -> Point2<f32> {
+    Point2::new(
+        pos.0 + square_size * ((id % size) as f32 + 0.5),
+        pos.1 + square_size * ((id / size) as f32 + 0.5),
+    )
 }
 
-pub(crate) fn draw_player1(mb: &mut MeshBuilder, pos_x: usize, pos_y: usize) {
-    mb.circle(
-        graphics::DrawMode::stroke(4.0),
-        Point2::new(
-            PLAY_FIELD_POS.0 + (pos_x as f32 + 0.5) * SQUARE_SIZE,
-            PLAY_FIELD_POS.1 + (pos_y as f32 + 0.5) * SQUARE_SIZE,
-        ),
-        SQUARE_SIZE as f32 / 4.0,
-        0.00001,
-        graphics::BLACK,
-    );
+/// The first `part` of the line from `from` to `to`, none of it below 0 and
+/// all of it from 1 on.
+fn draw_line_part(
+    mb: &mut MeshBuilder,
+    from: Point2<f32>,
+    to: Point2<f32>,
+    part: f32,
+    width: f32,
+    color: Color,
+) {
+    if part <= 0.0 {
+        return;
+    }
+    let to = from + (to - from) * part.min(1.0);
+    let _ = mb.line(&[from, to], width, color);
 }
 
-pub(crate) fn draw_player2(mb: &mut MeshBuilder, pos_x: usize, pos_y: usize) {
-    let _ = mb.line(
-        &[
-            Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (pos_x as f32 + 0.25),
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (pos_y as f32 + 0.25),
-            ),
-            Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (pos_x as f32 + 0.75),
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (pos_y as f32 + 0.75),
-            ),
-        ],
-        4.0,
-        graphics::BLACK,
-    );
-    let _ = mb.line(
-        &[
-            Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (pos_x as f32 + 0.75),
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (pos_y as f32 + 0.25),
-            ),
+/// Where a mark image goes in the square at (`pos_x`, `pos_y`).
+fn mark_rect(pos: (f32, f32), square_size: f32, pos_x: usize, pos_y: usize) -> graphics::Rect {
+    graphics::Rect::new(
+        pos.0 + square_size * (pos_x as f32 + 0.1),
+        pos.1 + square_size * (pos_y as f32 + 0.1),
+        square_size * 0.8,
+        square_size * 0.8,
+    )
+}
+
+/// `number` centered in the square at (`pos_x`, `pos_y`). Text isn't part of
+/// the mesh, so this draws straight away.
+pub(crate) fn draw_number(
+    ctx: &mut Context,
+    pos: (f32, f32),
+    square_size: f32,
+    number: u8,
+    pos_x: usize,
+    pos_y: usize,
+    color: Color,
+) {
+    let text =
+        Text::new(TextFragment::new(number.to_string()).scale(Scale::uniform(square_size * 0.6)));
+    let (width, height) = text.dimensions(ctx);
+    let _ = graphics::draw(
+        ctx,
+        &text,
+        (
             Point2::new(
-                PLAY_FIELD_POS.0 + SQUARE_SIZE * (pos_x as f32 + 0.25),
-                PLAY_FIELD_POS.1 + SQUARE_SIZE * (pos_y as f32 + 0.75),
+                pos.0 + (pos_x as f32 + 0.5) * square_size - width as f32 / 2.0,
+                pos.1 + (pos_y as f32 + 0.5) * square_size - height as f32 / 2.0,
             ),
-        ],
-        4.0,
-        graphics::BLACK,
+            color,
+        ),
     );
 }
 
-pub(crate) fn draw_player(mb: &mut MeshBuilder, player: Player, pos_x: usize, pos_y: usize) {
-    match player {
-        Player::Player1 => draw_player1(mb, pos_x, pos_y),
-        Player::Player2 => draw_player2(mb, pos_x, pos_y),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_human_can_play_noughts() {
+        let mut painter = Painter::new(Theme::CLASSIC, Mark::Cross);
+        assert_eq!(painter.player_mark(Player::Player2), Mark::Cross);
+        painter.set_human_mark(Mark::Nought);
+        assert_eq!(painter.player_mark(Player::Player2), Mark::Nought);
+        assert_eq!(painter.player_mark(Player::Player1), Mark::Cross);
     }
-}
 
-pub(crate) fn draw_text(ctx: &mut Context, text: &str) {
-    let fps_display = Text::new(format!("Game: {}\nPress 'R' to restart", text));
-    // When drawing through these calls, `DrawParam` will work as they are documented.
-    let _ = graphics::draw(
-        ctx,
-        &fps_display,
-        (Point2::new(0.0, SCREEN_SIZE.1 * 0.9), graphics::BLACK),
-    );
+    #[test]
+    fn mark_images_follow_a_swapped_mark() {
+        let theme = Theme {
+            nought_image: Some(String::from("/o.png")),
+            cross_image: Some(String::from("/x.png")),
+            ..Theme::CLASSIC
+        };
+        let painter = Painter::new(theme, Mark::Nought);
+        let human = painter.player_mark(Player::Player2);
+        let computer = painter.player_mark(Player::Player1);
+        assert_eq!(painter.theme().mark_image(human), Some("/o.png"));
+        assert_eq!(painter.theme().mark_image(computer), Some("/x.png"));
+    }
 }
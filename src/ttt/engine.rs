@@ -0,0 +1,226 @@
+use super::config::{AI_BOOK_VARIETY, AI_TIE_BREAK};
+use super::external_engine::ExternalEngine;
+use super::game::{Cell, Game, GameState, Player, RuleSet, Topology};
+use super::game_logic::{Ai, Difficulty, TieBreak};
+use super::mcts::MctsEngine;
+use super::rng::Rng;
+
+const WIN_SCORE: i32 = 100;
+
+/// Anything that can pick a move for either side of a 3x3 game.
+pub trait Engine {
+    fn name(&self) -> String;
+
+    fn choose_move(&mut self, cells: &[Cell], side: Player) -> Option<usize>;
+}
+
+/// Builds an engine from a spec such as `minimax:3`, `random`, `mcts:2000`,
+/// `perfect` or `external:<command>`.
+pub fn from_spec(spec: &str) -> Result<Box<dyn Engine>, String> {
+    let (kind, arg) = match spec.find(':') {
+        Some(i) => (&spec[..i], Some(&spec[i + 1..])),
+        None => (spec, None),
+    };
+    let number = |default: usize| -> Result<usize, String> {
+        arg.map_or(Ok(default), |a| {
+            a.parse()
+                .map_err(|_| format!("bad number '{}' in engine '{}'", a, spec))
+        })
+    };
+
+    match kind {
+        "minimax" => {
+            let depth = match arg {
+                Some(_) => Some(number(0)?),
+                None => None,
+            };
+            Ok(Box::new(MinimaxEngine::new(depth, AI_TIE_BREAK)))
+        }
+        "random" => Ok(Box::new(RandomEngine::new(Rng::from_time()))),
+        "mcts" => Ok(Box::new(MctsEngine::new(number(1000)?, Rng::from_time()))),
+        "perfect" => Ok(Box::new(Ai::new(AI_TIE_BREAK, AI_BOOK_VARIETY))),
+        "external" => match arg {
+            Some(command) => ExternalEngine::spawn(command)
+                .map(|engine| Box::new(engine) as Box<dyn Engine>)
+                .map_err(|e| format!("can't start '{}': {}", command, e)),
+            None => Err(String::from("external engine needs a command")),
+        },
+        _ => Err(format!("unknown engine '{}'", spec)),
+    }
+}
+
+pub(crate) fn empty_cells(cells: &[Cell]) -> Vec<usize> {
+    (0..cells.len())
+        .filter(|&i| cells[i] == Cell::Empty)
+        .collect()
+}
+
+impl Engine for Ai {
+    fn name(&self) -> String {
+        match self.difficulty {
+            Difficulty::Hard => String::from("perfect"),
+            difficulty => String::from(difficulty.name()),
+        }
+    }
+
+    fn choose_move(&mut self, cells: &[Cell], side: Player) -> Option<usize> {
+        Ai::choose_move(self, cells, side, RuleSet::Standard, Topology::Flat.lines())
+    }
+}
+
+pub struct RandomEngine {
+    rng: Rng,
+}
+
+impl RandomEngine {
+    pub fn new(rng: Rng) -> Self {
+        RandomEngine { rng }
+    }
+}
+
+impl Engine for RandomEngine {
+    fn name(&self) -> String {
+        String::from("random")
+    }
+
+    fn choose_move(&mut self, cells: &[Cell], _side: Player) -> Option<usize> {
+        let moves = empty_cells(cells);
+        if moves.is_empty() {
+            None
+        } else {
+            Some(moves[self.rng.below(moves.len())])
+        }
+    }
+}
+
+/// Negamax with alpha-beta pruning that stops after `depth` plies and scores
+/// the leaves by the lines each side can still complete.
+pub struct MinimaxEngine {
+    depth: Option<usize>,
+    tie_break: TieBreak,
+}
+
+impl MinimaxEngine {
+    pub fn new(depth: Option<usize>, tie_break: TieBreak) -> Self {
+        MinimaxEngine { depth, tie_break }
+    }
+
+    fn search(
+        cells: &mut [Cell],
+        side: Player,
+        depth: usize,
+        max_depth: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let state = Game::get_game_state(cells);
+        if state != GameState::InProgress {
+            let score = WIN_SCORE - depth as i32;
+            return match state.winner() {
+                Some(player) if player == side => score,
+                Some(_) => -score,
+                None => 0,
+            };
+        }
+        if depth >= max_depth {
+            return evaluate(cells, side);
+        }
+
+        let mut best = -WIN_SCORE;
+        for i in empty_cells(cells) {
+            cells[i] = Cell::Player(side);
+            let score =
+                -MinimaxEngine::search(cells, side.opponent(), depth + 1, max_depth, -beta, -alpha);
+            cells[i] = Cell::Empty;
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Engine for MinimaxEngine {
+    fn name(&self) -> String {
+        match self.depth {
+            Some(depth) => format!("minimax:{}", depth),
+            None => String::from("minimax"),
+        }
+    }
+
+    fn choose_move(&mut self, cells: &[Cell], side: Player) -> Option<usize> {
+        let mut cells = cells.to_vec();
+        let max_depth = self.depth.unwrap_or(cells.len()).max(1);
+        let mut best_score = i32::MIN;
+        let mut best_moves = Vec::new();
+
+        for i in empty_cells(&cells) {
+            cells[i] = Cell::Player(side);
+            let score = -MinimaxEngine::search(
+                &mut cells,
+                side.opponent(),
+                1,
+                max_depth,
+                -WIN_SCORE,
+                WIN_SCORE,
+            );
+            cells[i] = Cell::Empty;
+
+            if score > best_score {
+                best_score = score;
+                best_moves.clear();
+            }
+            if score == best_score {
+                best_moves.push(i);
+            }
+        }
+        self.tie_break.pick(&best_moves)
+    }
+}
+
+/// Counts marks on lines still open for each side.
+fn evaluate(cells: &[Cell], side: Player) -> i32 {
+    let mut score = 0;
+    for line in Topology::Flat.lines() {
+        let ours = line
+            .iter()
+            .filter(|&&i| cells[i] == Cell::Player(side))
+            .count() as i32;
+        let theirs = line
+            .iter()
+            .filter(|&&i| cells[i] == Cell::Player(side.opponent()))
+            .count() as i32;
+        if theirs == 0 {
+            score += ours;
+        }
+        if ours == 0 {
+            score -= theirs;
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shallow_minimax_blocks_threat() {
+        let mut cells = vec![Cell::Empty; 9];
+        cells[0] = Cell::Player(Player::Player2);
+        cells[1] = Cell::Player(Player::Player2);
+        cells[4] = Cell::Player(Player::Player1);
+        let mut engine = MinimaxEngine::new(Some(2), TieBreak::First);
+        assert_eq!(engine.choose_move(&cells, Player::Player1), Some(2));
+    }
+
+    #[test]
+    fn parses_specs() {
+        assert_eq!(from_spec("minimax:3").unwrap().name(), "minimax:3");
+        assert_eq!(from_spec("mcts:50").unwrap().name(), "mcts:50");
+        assert!(from_spec("minimax:deep").is_err());
+        assert!(from_spec("chess").is_err());
+    }
+}
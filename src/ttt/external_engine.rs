@@ -0,0 +1,101 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use super::engine::Engine;
+use super::game::{Cell, Player};
+
+/// Engine running as a separate process and talking a line based protocol
+/// over stdin/stdout. For every move it receives
+///
+/// ```text
+/// position <cells> <side>
+/// ```
+///
+/// where `<cells>` has one character per cell in row order (`.` empty,
+/// `O` Player1, `X` Player2) and `<side>` is `1` or `2`, and it must answer
+/// with `move <cell>`. The engine is sent `quit` and then killed when dropped.
+pub struct ExternalEngine {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ExternalEngine {
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(ExternalEngine {
+            command: String::from(command),
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn request_move(&mut self, cells: &[Cell], side: Player) -> io::Result<usize> {
+        let board: String = cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Empty => '.',
+                Cell::Blocked => '*',
+                Cell::Player(Player::Player1) => 'O',
+                Cell::Player(Player::Player2) => 'X',
+            })
+            .collect();
+        let side = match side {
+            Player::Player1 => 1,
+            Player::Player2 => 2,
+        };
+        writeln!(self.stdin, "position {} {}", board, side)?;
+        self.stdin.flush()?;
+
+        let mut line = String::new();
+        self.stdout.read_line(&mut line)?;
+        line.trim()
+            .strip_prefix("move ")
+            .and_then(|cell| cell.trim().parse().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected reply '{}'", line.trim()),
+                )
+            })
+    }
+}
+
+impl Engine for ExternalEngine {
+    fn name(&self) -> String {
+        self.command
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// Protocol errors and illegal replies count as having no move, which
+    /// forfeits the game.
+    fn choose_move(&mut self, cells: &[Cell], side: Player) -> Option<usize> {
+        match self.request_move(cells, side) {
+            Ok(cell) if cells.get(cell) == Some(&Cell::Empty) => Some(cell),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for ExternalEngine {
+    fn drop(&mut self) {
+        let _ = writeln!(self.stdin, "quit");
+        let _ = self.stdin.flush();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -1,11 +1,143 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use super::animation::BoardAnimation;
+use super::clock::{Clock, TimeControl};
 use super::config::{PLAY_FIELD_POS, PLAY_FIELD_SIZE, SQUARE_SIZE};
+use super::engine::Engine;
+use super::game_logic::{self, Ai};
+use super::rating::{Rating, RatingStore};
+use super::record::GameRecord;
+use super::series::Series;
+use super::shape::BoardShape;
+use super::stats::{self, GameOutcome, History, HistoryEntry};
+use super::vanishing::{self, VanishingPosition};
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Player {
     Player1,
     Player2,
 }
 
+impl Player {
+    pub(crate) fn opponent(self) -> Player {
+        match self {
+            Player::Player1 => Player::Player2,
+            Player::Player2 => Player::Player1,
+        }
+    }
+
+    /// 0 for `Player1` and 1 for `Player2`, for arrays with a slot per player.
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Player::Player1 => 0,
+            Player::Player2 => 1,
+        }
+    }
+
+    /// The symbol the player places in the other variants.
+    pub(crate) fn mark(self) -> Mark {
+        match self {
+            Player::Player1 => Mark::Nought,
+            Player::Player2 => Mark::Cross,
+        }
+    }
+}
+
+static FLAT_LINES: OnceLock<Vec<Vec<usize>>> = OnceLock::new();
+static TORUS_LINES: OnceLock<Vec<Vec<usize>>> = OnceLock::new();
+
+/// How the edges of the classic board connect.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Topology {
+    Flat,
+    /// Lines run off one edge and come back in on the opposite one, so
+    /// c1-a1-b1 is a row and every diagonal has two wrapped partners.
+    Torus,
+}
+
+impl Topology {
+    /// Every line of `PLAY_FIELD_SIZE` cells: rows, columns, diagonals and
+    /// then anti-diagonals, each listed in the order it is walked.
+    pub(crate) fn lines(self) -> &'static [Vec<usize>] {
+        let lines = match self {
+            Topology::Flat => &FLAT_LINES,
+            Topology::Torus => &TORUS_LINES,
+        };
+        lines.get_or_init(|| {
+            let size = PLAY_FIELD_SIZE as isize;
+            let line = |x: isize, y: isize, (dx, dy): (isize, isize)| {
+                (0..size)
+                    .map(|i| {
+                        let (x, y) = (x + dx * i, y + dy * i);
+                        let inside = (0..size).contains(&x) && (0..size).contains(&y);
+                        if self == Topology::Flat && !inside {
+                            return None;
+                        }
+                        Some((x.rem_euclid(size) + y.rem_euclid(size) * size) as usize)
+                    })
+                    .collect::<Option<Vec<usize>>>()
+            };
+
+            let mut lines = Vec::new();
+            lines.extend((0..size).filter_map(|y| line(0, y, (1, 0))));
+            lines.extend((0..size).filter_map(|x| line(x, 0, (0, 1))));
+            lines.extend((0..size).filter_map(|x| line(x, 0, (1, 1))));
+            lines.extend((0..size).rev().filter_map(|x| line(x, 0, (-1, 1))));
+            lines
+        })
+    }
+
+    /// First line that `complete` accepts. Lines are won by equal marks in
+    /// most variants, but not all of them.
+    pub(crate) fn find_line(self, complete: impl Fn(&[usize]) -> bool) -> Option<&'static [usize]> {
+        self.lines()
+            .iter()
+            .map(Vec::as_slice)
+            .find(|line| complete(line))
+    }
+}
+
+/// One of each pair of opposite directions a line can run in on a grid.
+pub(crate) const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// Cells of the run of pieces equal to the one at `id` that passes through
+/// `id` along `direction`, in order, on a grid `width` cells wide.
+pub(crate) fn run_through<T: PartialEq>(
+    cells: &[T],
+    width: usize,
+    id: usize,
+    direction: (isize, isize),
+) -> Vec<usize> {
+    let (width, height) = (width as isize, (cells.len() / width) as isize);
+    let at = |x: isize, y: isize| {
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            let other = (x + y * width) as usize;
+            if cells[other] == cells[id] {
+                return Some(other);
+            }
+        }
+        None
+    };
+
+    let (x, y) = (id as isize % width, id as isize / width);
+    let mut start = 0;
+    while at(x - (start + 1) * direction.0, y - (start + 1) * direction.1).is_some() {
+        start += 1;
+    }
+    (-start..)
+        .map_while(|i| at(x + i * direction.0, y + i * direction.1))
+        .collect()
+}
+
+/// Symbol on the board. Normally each player owns one, in the wild variant
+/// either player may place either.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mark {
+    Nought,
+    Cross,
+}
+
 impl std::fmt::Display for Player {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -14,11 +146,58 @@ impl std::fmt::Display for Player {
 
 #[derive(Debug, PartialEq)]
 pub enum GameState {
-    GameWon { player: Player, cells: Vec<usize> },
+    GameWon {
+        player: Player,
+        cells: Vec<usize>,
+    },
+    /// Misère: `player` completed the line in `cells` and lost.
+    GameLost {
+        player: Player,
+        cells: Vec<usize>,
+    },
+    /// `player` ran out of time on their clock.
+    LostOnTime {
+        player: Player,
+    },
     Tie,
     InProgress,
 }
 
+impl GameState {
+    /// The player who won a finished game, `None` for a tie or a game in progress.
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameState::GameWon { player, .. } => Some(*player),
+            GameState::GameLost { player, .. } | GameState::LostOnTime { player } => {
+                Some(player.opponent())
+            }
+            GameState::Tie | GameState::InProgress => None,
+        }
+    }
+}
+
+/// What completing a line means.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RuleSet {
+    Standard,
+    /// Reverse tic-tac-toe: whoever completes a line loses.
+    Misere,
+    /// Each player keeps at most three marks, placing a fourth removes the
+    /// oldest one. Repeating a position three times draws.
+    Vanishing,
+}
+
+impl RuleSet {
+    pub fn game_state(self, cells: &[Cell], lines: &[Vec<usize>]) -> GameState {
+        match (self, Game::get_game_state_on(cells, lines)) {
+            (RuleSet::Misere, GameState::GameWon { player, cells }) => {
+                GameState::GameLost { player, cells }
+            }
+            (_, state) => state,
+        }
+    }
+}
+
 impl std::fmt::Display for GameState {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -29,16 +208,34 @@ impl std::fmt::Display for GameState {
 pub enum Cell {
     Empty,
     Player(Player),
+    /// Not part of the board; see `BoardShape`.
+    Blocked,
 }
 
 pub struct Game {
     cell_states: Vec<Cell>,
     selected_cell: SelectedCell,
+    moves: Vec<usize>,
+    rules: RuleSet,
+    topology: Topology,
+    shape: BoardShape,
+    clock: Option<Clock>,
+    series: Option<Series>,
+    /// Who moved first in this game.
+    first: Player,
+    /// Whoever ran out of time.
+    flagged: Option<Player>,
+    ai: Ai,
+    ratings: Option<RatingStore>,
+    player_name: String,
+    history: Option<History>,
+    animation: BoardAnimation,
 }
 
 #[derive(PartialEq)]
 pub enum FieldType {
     PlayField,
+    BlockedField,
     OutField,
 }
 
@@ -56,19 +253,291 @@ pub enum ChangeSelected {
 }
 
 impl Game {
-    pub(crate) fn new() -> Self {
+    pub fn new(ai: Ai, rules: RuleSet) -> Self {
         // The ttf file will be in your resources directory. Later, we
         // will mount that directory so we can omit it in the path here.
         Game {
             cell_states: vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE],
             selected_cell: SelectedCell::NotSelected,
+            moves: Vec::new(),
+            rules,
+            topology: Topology::Flat,
+            shape: BoardShape::default(),
+            clock: None,
+            series: None,
+            first: Player::Player2,
+            flagged: None,
+            ai,
+            ratings: None,
+            player_name: String::from("player"),
+            history: None,
+            animation: BoardAnimation::new(PLAY_FIELD_SIZE * PLAY_FIELD_SIZE),
         }
     }
 
     pub(crate) fn clear(&mut self) {
-        *self = Game::new();
-        // self.cell_states = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
-        // self.S
+        self.cell_states = self.shape.empty_cells();
+        self.selected_cell = SelectedCell::NotSelected;
+        self.moves.clear();
+        self.flagged = None;
+        if let Some(clock) = &mut self.clock {
+            clock.reset();
+        }
+    }
+
+    /// Rates every finished game of `player_name` against the computer.
+    pub fn set_ratings(&mut self, ratings: RatingStore, player_name: String) {
+        self.ratings = Some(ratings);
+        self.player_name = player_name;
+    }
+
+    pub(crate) fn player_rating(&self) -> Option<(&str, Rating)> {
+        self.ratings.as_ref().map(|store| {
+            (
+                self.player_name.as_str(),
+                store.ratings().get(&self.player_name),
+            )
+        })
+    }
+
+    /// Keeps every finished game in `history`.
+    pub fn set_history(&mut self, history: History) {
+        self.history = Some(history);
+    }
+
+    /// Rules and board the game is played with, for the history.
+    fn mode(&self) -> String {
+        let mut mode = String::from(match self.rules {
+            RuleSet::Standard => "classic",
+            RuleSet::Misere => "misere",
+            RuleSet::Vanishing => "vanishing",
+        });
+        if self.topology == Topology::Torus {
+            mode += " torus";
+        }
+        if self.shape != BoardShape::default() {
+            mode += " shape";
+        }
+        mode
+    }
+
+    /// Updates the ratings, the match score and the history once a game is
+    /// over.
+    pub(crate) fn record_finished_game(&mut self) {
+        let state = self.get_state();
+        if state != GameState::InProgress {
+            self.record_game(state.winner());
+        }
+    }
+
+    /// Scores, rates and keeps the game as won by `winner`.
+    fn record_game(&mut self, winner: Option<Player>) {
+        if let Some(series) = &mut self.series {
+            series.record(winner);
+        }
+        if let Some(store) = &mut self.ratings {
+            let record = GameRecord {
+                player1: self.ai.name(),
+                player2: self.player_name.clone(),
+                first: self.first,
+                moves: self.moves.clone(),
+                winner,
+            };
+            if let Err(e) = store.record(&record) {
+                eprintln!("Can't save ratings: {}", e);
+            }
+        }
+        if self.history.is_some() {
+            let entry = HistoryEntry {
+                date: stats::now(),
+                mode: self.mode(),
+                opponent: self.ai.name(),
+                difficulty: self.ai.difficulty,
+                outcome: GameOutcome::from_winner(winner),
+                first: self.first,
+                moves: self.moves.clone(),
+            };
+            if let Some(history) = &mut self.history {
+                if let Err(e) = history.record(entry) {
+                    eprintln!("Can't save game history: {}", e);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// Makes lines wrap around the edges of the board, or stop at them.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    pub(crate) fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Blocks cells of the board and possibly replaces its lines. Like the
+    /// topology, shapes are only played with standard and misère rules.
+    pub fn set_shape(&mut self, shape: BoardShape) {
+        self.shape = shape;
+        self.clear();
+    }
+
+    /// Plays with clocks from now on.
+    pub fn set_clock(&mut self, control: TimeControl) {
+        self.clock = Some(Clock::new(control));
+    }
+
+    pub(crate) fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Runs the human's clock while it's their move.
+    pub(crate) fn tick_clock(&mut self, elapsed: Duration) {
+        if self.get_state() != GameState::InProgress {
+            return;
+        }
+        if let Some(clock) = &mut self.clock {
+            if clock.tick(Player::Player2, elapsed) {
+                self.flagged = Some(Player::Player2);
+                self.record_finished_game();
+            }
+        }
+    }
+
+    /// Multiplies the speed marks and the winning line are drawn at, 0
+    /// draws them at once.
+    pub fn set_animation_speed(&mut self, speed: f32) {
+        self.animation.set_speed(speed);
+    }
+
+    pub(crate) fn animation(&self) -> &BoardAnimation {
+        &self.animation
+    }
+
+    /// Moves the animations of the board on by `elapsed`.
+    pub(crate) fn animate(&mut self, elapsed: Duration) {
+        let (marks, won) = self.animated();
+        self.animation.advance(elapsed, &marks, won);
+    }
+
+    /// Whether marks or the winning line are still being drawn.
+    pub(crate) fn is_animating(&self) -> bool {
+        let (marks, won) = self.animated();
+        marks.iter().any(|&id| self.animation.mark(id) < 1.0)
+            || (won && self.animation.win_line() < 1.0)
+    }
+
+    /// The marks on the board in the order they were played, and whether
+    /// there is a line through some of them.
+    fn animated(&self) -> (Vec<usize>, bool) {
+        let marks = self
+            .moves
+            .iter()
+            .copied()
+            .filter(|&id| self.cell_states[id] != Cell::Empty)
+            .collect();
+        let won = matches!(
+            self.get_state(),
+            GameState::GameWon { .. } | GameState::GameLost { .. }
+        );
+        (marks, won)
+    }
+
+    /// Plays a best-of-`games` match from now on.
+    pub fn set_series(&mut self, games: usize) {
+        self.series = Some(Series::new(games));
+    }
+
+    pub(crate) fn series(&self) -> Option<&Series> {
+        self.series.as_ref()
+    }
+
+    /// Starts over with `first` moving first in this and, outside a match,
+    /// every game; in a match the players still take turns.
+    pub fn set_first(&mut self, first: Player) {
+        self.first = first;
+        if let Some(series) = &mut self.series {
+            *series = Series::starting_with(series.best_of(), first);
+        }
+        self.start_over();
+    }
+
+    /// What R does: outside a match it clears the board. In a match it
+    /// starts the next game, with the other player moving first, and a new
+    /// match once the match is decided. Leaving a match game that has begun
+    /// resigns it, so a lost position can't just be wiped.
+    pub(crate) fn restart(&mut self) {
+        let mut finished = self.get_state() != GameState::InProgress;
+        if self.series.is_some() && !finished && !self.moves.is_empty() {
+            self.record_game(Some(Player::Player1));
+            finished = true;
+        }
+        if let Some(series) = &mut self.series {
+            if finished && series.is_over() {
+                *series = Series::starting_with(series.best_of(), series.first());
+            }
+            if finished {
+                self.first = series.next_first();
+            }
+        }
+        self.start_over();
+    }
+
+    /// Clears the board, fading out its marks, and lets the computer open if
+    /// it moves first.
+    fn start_over(&mut self) {
+        let marks = (0..self.cell_states.len())
+            .filter_map(|id| match self.cell_states[id] {
+                Cell::Player(player) => Some((id, player)),
+                _ => None,
+            })
+            .collect();
+        self.animation.clear_board(marks);
+        self.clear();
+        if self.first == Player::Player1 {
+            self.play_computer_move();
+        }
+    }
+
+    /// After the human has moved: presses their clock, lets the computer
+    /// answer within its time and records the game if that ended it.
+    pub(crate) fn answer_human_move(&mut self) {
+        if let Some(clock) = &mut self.clock {
+            clock.finish_move(Player::Player2);
+        }
+        self.play_computer_move();
+        self.record_finished_game();
+    }
+
+    fn play_computer_move(&mut self) {
+        if let Some(clock) = &self.clock {
+            self.ai.time_budget = Some(clock.budget(Player::Player1));
+        }
+        let started = Instant::now();
+        game_logic::make_best_move(self);
+        if let Some(clock) = &mut self.clock {
+            let flagged = clock.tick(Player::Player1, started.elapsed());
+            clock.finish_move(Player::Player1);
+            if flagged && self.get_state() == GameState::InProgress {
+                self.flagged = Some(Player::Player1);
+            }
+        }
+    }
+
+    pub(crate) fn shape(&self) -> &BoardShape {
+        &self.shape
+    }
+
+    /// Lines that win in this game.
+    pub(crate) fn lines(&self) -> &[Vec<usize>] {
+        self.shape.lines(self.topology)
+    }
+
+    pub(crate) fn ai_mut(&mut self) -> &mut Ai {
+        &mut self.ai
     }
 
     pub(crate) fn get_field_type(x: f32, y: f32) -> FieldType {
@@ -83,12 +552,54 @@ impl Game {
         FieldType::OutField
     }
 
+    /// Like `get_field_type`, but tells blocked cells of the shape apart.
+    pub(crate) fn get_shape_field_type(&self, x: f32, y: f32) -> FieldType {
+        let field_type = Game::get_field_type(x, y);
+        let (x, y) = Game::get_cell(x, y);
+        if field_type == FieldType::PlayField && self.shape.is_blocked(x + y * PLAY_FIELD_SIZE) {
+            return FieldType::BlockedField;
+        }
+        field_type
+    }
+
     pub(crate) fn get_selected_cell(&self) -> SelectedCell {
         self.selected_cell.clone()
     }
 
     pub(crate) fn get_state(&self) -> GameState {
-        Game::get_game_state(&self.cell_states)
+        if let Some(player) = self.flagged {
+            return GameState::LostOnTime { player };
+        }
+        match self.rules {
+            RuleSet::Vanishing => vanishing::game_state(&self.moves, self.first),
+            rules => rules.game_state(&self.cell_states, self.lines()),
+        }
+    }
+
+    pub(crate) fn moves(&self) -> &[usize] {
+        &self.moves
+    }
+
+    pub(crate) fn first(&self) -> Player {
+        self.first
+    }
+
+    /// The mark of `player` that disappears with their next move.
+    pub(crate) fn oldest_mark(&self, player: Player) -> Option<usize> {
+        match self.rules {
+            RuleSet::Vanishing => {
+                VanishingPosition::from_moves(&self.moves, self.first).oldest_mark(player)
+            }
+            _ => None,
+        }
+    }
+
+    fn place(&mut self, id: usize, player: Player) {
+        if let Some(oldest) = self.oldest_mark(player) {
+            self.cell_states[oldest] = Cell::Empty;
+        }
+        self.cell_states[id] = Cell::Player(player);
+        self.moves.push(id);
     }
 
     pub(crate) fn get_cell(x: f32, y: f32) -> (usize, usize) {
@@ -107,113 +618,81 @@ impl Game {
     }
 
     pub(crate) fn get_cell_state(&self, row: usize, column: usize) -> Cell {
-        self.cell_states[row + column * PLAY_FIELD_SIZE as usize]
+        self.cell_states[row + column * PLAY_FIELD_SIZE]
     }
 
-    pub(crate) fn set_cell_state(&mut self, row: usize, column: usize, cell_state: Cell) -> bool {
-        if self.cell_states[row + column * PLAY_FIELD_SIZE as usize] == Cell::Empty {
-            self.cell_states[row + column * PLAY_FIELD_SIZE as usize] = cell_state;
+    pub(crate) fn set_cell_state(&mut self, row: usize, column: usize, player: Player) -> bool {
+        if self.cell_states[row + column * PLAY_FIELD_SIZE] == Cell::Empty {
+            self.place(row + column * PLAY_FIELD_SIZE, player);
             return true;
         }
         false
     }
 
+    /// Moves the selection one cell on, jumping over blocked cells; it stays
+    /// put at the edge of the board.
     pub(crate) fn move_selected_cell(&mut self, direction: ChangeSelected) {
-        match self.get_state() {
-            GameState::GameWon { .. } => return,
-            _ => (),
-        }
-        if self.selected_cell == SelectedCell::NotSelected {
-            self.selected_cell = SelectedCell::Selected { x: 0, y: 0 };
+        if self.get_state().winner().is_some() {
             return;
         }
-        match direction {
-            ChangeSelected::Down => {
-                if let SelectedCell::Selected { x, y } = self.selected_cell {
-                    if y < PLAY_FIELD_SIZE - 1 {
-                        self.selected_cell = SelectedCell::Selected { x: x, y: y + 1 };
-                    }
-                }
-            }
-            ChangeSelected::Up => {
-                if let SelectedCell::Selected { x, y } = self.selected_cell {
-                    if y > 0 {
-                        self.selected_cell = SelectedCell::Selected { x: x, y: y - 1 };
-                    }
+        let (mut x, mut y) = match self.selected_cell {
+            SelectedCell::Selected { x, y } => (x as isize, y as isize),
+            SelectedCell::NotSelected => {
+                let first = (0..self.cell_states.len()).find(|&id| !self.shape.is_blocked(id));
+                if let Some(id) = first {
+                    self.selected_cell = SelectedCell::Selected {
+                        x: id % PLAY_FIELD_SIZE,
+                        y: id / PLAY_FIELD_SIZE,
+                    };
                 }
+                return;
             }
-            ChangeSelected::Right => {
-                if let SelectedCell::Selected { x, y } = self.selected_cell {
-                    if x < PLAY_FIELD_SIZE - 1 {
-                        self.selected_cell = SelectedCell::Selected { x: x + 1, y: y };
-                    }
-                }
+        };
+        let (dx, dy) = match direction {
+            ChangeSelected::Left => (-1, 0),
+            ChangeSelected::Up => (0, -1),
+            ChangeSelected::Right => (1, 0),
+            ChangeSelected::Down => (0, 1),
+        };
+        let size = PLAY_FIELD_SIZE as isize;
+        loop {
+            x += dx;
+            y += dy;
+            if !(0..size).contains(&x) || !(0..size).contains(&y) {
+                return;
             }
-            ChangeSelected::Left => {
-                if let SelectedCell::Selected { x, y } = self.selected_cell {
-                    if x > 0 {
-                        self.selected_cell = SelectedCell::Selected { x: x - 1, y: y };
-                    }
-                }
+            if !self.shape.is_blocked((x + y * size) as usize) {
+                break;
             }
         }
+        self.selected_cell = SelectedCell::Selected {
+            x: x as usize,
+            y: y as usize,
+        };
     }
 
     pub(crate) fn get_game_state(cell_states: &[Cell]) -> GameState {
-        for i in 0..3 {
-            if cell_states[i * 3] != Cell::Empty
-                && cell_states[i * 3] == cell_states[i * 3 + 1]
-                && cell_states[i * 3 + 1] == cell_states[i * 3 + 2]
-            {
-                if let Cell::Player(player) = cell_states[i * 3] {
-                    return GameState::GameWon {
-                        player: player,
-                        cells: vec![i * 3, i * 3 + 1, i * 3 + 2],
-                    };
-                }
-            }
-        }
-        for i in 0..3 {
-            if cell_states[i] != Cell::Empty
-                && cell_states[i] == cell_states[i + 3]
-                && cell_states[i + 3] == cell_states[i + 6]
-            {
-                if let Cell::Player(player) = cell_states[i] {
+        Game::get_game_state_on(cell_states, Topology::Flat.lines())
+    }
+
+    /// State of the board when any of `lines` wins.
+    pub(crate) fn get_game_state_on(cell_states: &[Cell], lines: &[Vec<usize>]) -> GameState {
+        for line in lines {
+            if let Cell::Player(player) = cell_states[line[0]] {
+                if line
+                    .iter()
+                    .all(|&id| cell_states[id] == cell_states[line[0]])
+                {
                     return GameState::GameWon {
-                        player: player,
-                        cells: vec![i, i + 3, i + 6],
+                        player,
+                        cells: line.clone(),
                     };
                 }
             }
         }
-        if cell_states[0] != Cell::Empty
-            && cell_states[0] == cell_states[4]
-            && cell_states[4] == cell_states[8]
-        {
-            if let Cell::Player(player) = cell_states[0] {
-                return GameState::GameWon {
-                    player: player,
-                    cells: vec![0, 4, 8],
-                };
-            }
-        }
 
-        if cell_states[2] != Cell::Empty
-            && cell_states[2] == cell_states[4]
-            && cell_states[4] == cell_states[6]
-        {
-            if let Cell::Player(player) = cell_states[2] {
-                return GameState::GameWon {
-                    player: player,
-                    cells: vec![2, 4, 6],
-                };
-            }
-        }
-
-        for i in 0..cell_states.len() {
-            if cell_states[i] == Cell::Empty {
-                return GameState::InProgress;
-            }
+        if cell_states.contains(&Cell::Empty) {
+            return GameState::InProgress;
         }
 
         GameState::Tie
@@ -224,10 +703,10 @@ impl Game {
     }
 
     pub(crate) fn make_move(&mut self, cell_id: usize, player: Player) {
-        let game_state = Game::get_game_state(&self.cell_states);
+        let game_state = self.get_state();
         if game_state == GameState::InProgress {
             if cell_id < self.cell_states.len() {
-                self.cell_states[cell_id] = Cell::Player(player);
+                self.place(cell_id, player);
             } else {
                 unreachable!();
             }
@@ -236,15 +715,15 @@ impl Game {
 
     pub(crate) fn make_move_on_selected_cell(&mut self, player: Player) -> bool {
         match self.selected_cell {
-            SelectedCell::NotSelected => return false,
+            SelectedCell::NotSelected => false,
             SelectedCell::Selected { x, y } => {
                 let index = x + PLAY_FIELD_SIZE * y;
                 match self.cell_states[index] {
                     Cell::Empty => {
-                        self.cell_states[index] = Cell::Player(player);
-                        return true;
+                        self.place(index, player);
+                        true
                     }
-                    _ => return false,
+                    _ => false,
                 }
             }
         }
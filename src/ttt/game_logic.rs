@@ -1,19 +1,236 @@
-use super::game::{Cell, Game, GameState, Player};
+use std::time::{Duration, Instant};
 
-fn max_search(elements: &mut [Cell], mut alpha: i32, beta: i32) -> i32 {
-    let game_state = Game::get_game_state(elements);
-    match game_state {
-        GameState::GameWon { player, .. } => {
-            return if player == Player::Player1 { 10 } else { -10 };
+use super::config::{AI_DIFFICULTY, PLAY_FIELD_SIZE};
+use super::game::{Cell, Game, GameState, Player, RuleSet, Topology};
+use super::opening_book;
+use super::position::Position;
+use super::rng::Rng;
+use super::tablebase;
+use super::vanishing::{self, VanishingPosition};
+
+const WIN_SCORE: i32 = 10;
+
+/// How the computer chooses between moves that score equally well.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TieBreak {
+    First,
+    Random(Rng),
+    CenterFirst,
+}
+
+impl TieBreak {
+    pub fn pick(&mut self, moves: &[usize]) -> Option<usize> {
+        match self {
+            TieBreak::First => moves.iter().copied().min(),
+            TieBreak::Random(rng) => {
+                if moves.is_empty() {
+                    None
+                } else {
+                    Some(moves[rng.below(moves.len())])
+                }
+            }
+            TieBreak::CenterFirst => moves
+                .iter()
+                .copied()
+                .min_by_key(|&id| (center_rank(id), id)),
+        }
+    }
+}
+
+impl std::str::FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(TieBreak::First),
+            "center" => Ok(TieBreak::CenterFirst),
+            "random" => Ok(TieBreak::Random(Rng::from_time())),
+            _ => match s.strip_prefix("random:") {
+                Some(seed) => seed
+                    .parse()
+                    .map(|seed| TieBreak::Random(Rng::new(seed)))
+                    .map_err(|_| format!("bad random seed '{}'", seed)),
+                None => Err(format!("unknown tie-break policy '{}'", s)),
+            },
+        }
+    }
+}
+
+/// How well the computer plays on the classic board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Takes a win when it sees one, but doesn't block.
+    Easy,
+    /// Also blocks the opponent's wins.
+    Medium,
+    /// Perfect play.
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// Plies searched, `None` for no limit.
+    fn search_limit(self) -> Option<i32> {
+        match self {
+            Difficulty::Easy => Some(1),
+            Difficulty::Medium => Some(2),
+            Difficulty::Hard => None,
         }
-        GameState::Tie => return 0,
-        GameState::InProgress => (),
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.name() == s)
+            .ok_or_else(|| format!("unknown difficulty '{}'", s))
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Settings and random state of the computer player.
+#[derive(Copy, Clone, Debug)]
+pub struct Ai {
+    /// Also decides how the opening book is used: a random tie-break samples
+    /// it with its own random state, the others play the heaviest move.
+    pub tie_break: TieBreak,
+    /// See `OpeningBook::pick`.
+    pub book_variety: f32,
+    pub difficulty: Difficulty,
+    /// Thinking time for the next move when playing on a clock.
+    pub time_budget: Option<Duration>,
+}
+
+impl Ai {
+    pub fn new(tie_break: TieBreak, book_variety: f32) -> Self {
+        Ai {
+            tie_break,
+            book_variety,
+            difficulty: AI_DIFFICULTY,
+            time_budget: None,
+        }
+    }
+
+    /// Consults the opening book first, then the tablebase, and only searches
+    /// when neither knows the position. Both are built for standard rules on
+    /// the whole flat board, so anything else always searches. Below `Hard`
+    /// the computer only ever searches, and not far.
+    pub fn choose_move(
+        &mut self,
+        cells: &[Cell],
+        side: Player,
+        rules: RuleSet,
+        lines: &[Vec<usize>],
+    ) -> Option<usize> {
+        let standard_board = lines == Topology::Flat.lines() && !cells.contains(&Cell::Blocked);
+        if let (Player::Player1, Some(limit)) = (side, self.difficulty.search_limit()) {
+            let best_moves = get_best_moves_to(&mut cells.to_vec(), rules, lines, limit);
+            return self.tie_break.pick(&best_moves);
+        }
+        if rules == RuleSet::Standard && standard_board {
+            let book = opening_book::global();
+            let book_move = match &mut self.tie_break {
+                TieBreak::Random(rng) => book.pick(cells, side, self.book_variety, rng),
+                TieBreak::First | TieBreak::CenterFirst => book.heaviest(cells, side),
+            };
+            if book_move.is_some() {
+                return book_move;
+            }
+
+            let best_moves = tablebase::global().best_moves(cells, side);
+            if !best_moves.is_empty() {
+                return self.tie_break.pick(&best_moves);
+            }
+        }
+        match (side, self.time_budget) {
+            (Player::Player1, Some(budget)) => {
+                let deadline = Instant::now() + budget;
+                let best_moves = get_best_moves_until(&mut cells.to_vec(), rules, lines, deadline);
+                self.tie_break.pick(&best_moves)
+            }
+            (Player::Player1, None) => {
+                get_best_move(&mut cells.to_vec(), rules, lines, &mut self.tie_break)
+            }
+            (Player::Player2, _) => None,
+        }
+    }
+
+    /// Move for `Player1` in a vanishing game where `first` moved first.
+    /// Which marks go next depends on the order they were placed in, so this
+    /// needs the whole game rather than the cells.
+    pub fn choose_vanishing_move(&mut self, moves: &[usize], first: Player) -> Option<usize> {
+        let mut position = VanishingPosition::new(first);
+        let mut history = Vec::new();
+        for &mv in moves {
+            history.push(position.clone());
+            position.play(mv);
+        }
+        if position.side_to_move() != Player::Player1 {
+            return None;
+        }
+        self.tie_break
+            .pick(&vanishing::best_moves(&position, &history))
+    }
+}
+
+/// Center first, then corners, then edges.
+fn center_rank(id: usize) -> (usize, bool) {
+    let center = PLAY_FIELD_SIZE / 2;
+    let dx = (id % PLAY_FIELD_SIZE).abs_diff(center);
+    let dy = (id / PLAY_FIELD_SIZE).abs_diff(center);
+    (dx.max(dy), dx != dy)
+}
+
+/// Wins found sooner score higher and losses found later score higher, so the
+/// computer finishes quickly and holds out as long as possible.
+fn terminal_score(game_state: &GameState, depth: i32) -> Option<i32> {
+    match game_state {
+        GameState::InProgress => None,
+        _ => Some(match game_state.winner() {
+            Some(Player::Player1) => WIN_SCORE - depth,
+            Some(Player::Player2) => depth - WIN_SCORE,
+            None => 0,
+        }),
+    }
+}
+
+fn max_search(
+    elements: &mut [Cell],
+    rules: RuleSet,
+    lines: &[Vec<usize>],
+    (depth, limit): (i32, i32),
+    mut alpha: i32,
+    beta: i32,
+) -> i32 {
+    if let Some(score) = terminal_score(&rules.game_state(elements, lines), depth) {
+        return score;
+    }
+    if depth == limit {
+        return 0;
     }
 
     for i in 0..elements.len() {
         if elements[i] == Cell::Empty {
             elements[i] = Cell::Player(Player::Player1);
-            let score = min_search(elements, alpha, beta);
+            let score = min_search(elements, rules, lines, (depth + 1, limit), alpha, beta);
             elements[i] = Cell::Empty;
             if score > alpha {
                 alpha = score;
@@ -26,20 +243,25 @@ fn max_search(elements: &mut [Cell], mut alpha: i32, beta: i32) -> i32 {
     alpha
 }
 
-fn min_search(elements: &mut [Cell], alpha: i32, mut beta: i32) -> i32 {
-    let game_state = Game::get_game_state(elements);
-    match game_state {
-        GameState::GameWon { player, .. } => {
-            return if player == Player::Player1 { 10 } else { -10 };
-        }
-        GameState::Tie => return 0,
-        GameState::InProgress => (),
+fn min_search(
+    elements: &mut [Cell],
+    rules: RuleSet,
+    lines: &[Vec<usize>],
+    (depth, limit): (i32, i32),
+    alpha: i32,
+    mut beta: i32,
+) -> i32 {
+    if let Some(score) = terminal_score(&rules.game_state(elements, lines), depth) {
+        return score;
+    }
+    if depth == limit {
+        return 0;
     }
 
     for i in 0..elements.len() {
         if elements[i] == Cell::Empty {
             elements[i] = Cell::Player(Player::Player2);
-            let score = max_search(elements, alpha, beta);
+            let score = max_search(elements, rules, lines, (depth + 1, limit), alpha, beta);
             elements[i] = Cell::Empty;
             if score < beta {
                 beta = score;
@@ -52,36 +274,297 @@ fn min_search(elements: &mut [Cell], alpha: i32, mut beta: i32) -> i32 {
     beta
 }
 
-pub fn get_best_move(elements: &mut [Cell]) -> Option<usize> {
-    let mut best_score = std::i32::MIN;
-    let mut best_move: usize = 0;
-    let mut best_move_found = false;
+/// All moves for `Player1` that share the best minimax score.
+pub fn get_best_moves(elements: &mut [Cell], rules: RuleSet, lines: &[Vec<usize>]) -> Vec<usize> {
+    get_best_moves_to(elements, rules, lines, i32::MAX)
+}
+
+/// Searches one ply deeper at a time and keeps the moves of the deepest
+/// search that finished by `deadline`; positions at the horizon score as
+/// draws. The first, one ply deep, always finishes.
+pub fn get_best_moves_until(
+    elements: &mut [Cell],
+    rules: RuleSet,
+    lines: &[Vec<usize>],
+    deadline: Instant,
+) -> Vec<usize> {
+    let empty = elements.iter().filter(|&&cell| cell == Cell::Empty).count() as i32;
+    let mut best_moves = get_best_moves_to(elements, rules, lines, 1);
+    for limit in 2..=empty {
+        if Instant::now() >= deadline {
+            break;
+        }
+        best_moves = get_best_moves_to(elements, rules, lines, limit);
+    }
+    best_moves
+}
+
+/// Best moves when looking `limit` plies ahead.
+fn get_best_moves_to(
+    elements: &mut [Cell],
+    rules: RuleSet,
+    lines: &[Vec<usize>],
+    limit: i32,
+) -> Vec<usize> {
+    let mut best_score = i32::MIN;
+    let mut best_moves = Vec::new();
 
     for i in 0..elements.len() {
         if elements[i] == Cell::Empty {
             elements[i] = Cell::Player(Player::Player1);
-            let tmp_score = min_search(elements, std::i32::MIN, std::i32::MAX);
+            let tmp_score = min_search(elements, rules, lines, (1, limit), i32::MIN, i32::MAX);
             elements[i] = Cell::Empty;
 
             if tmp_score > best_score {
                 best_score = tmp_score;
-                best_move = i;
-                best_move_found = true;
+                best_moves.clear();
+            }
+            if tmp_score == best_score {
+                best_moves.push(i);
             }
         }
     }
+    best_moves
+}
+
+pub fn get_best_move(
+    elements: &mut [Cell],
+    rules: RuleSet,
+    lines: &[Vec<usize>],
+    tie_break: &mut TieBreak,
+) -> Option<usize> {
+    tie_break.pick(&get_best_moves(elements, rules, lines))
+}
 
-    if best_move_found {
-        Some(best_move)
-    } else {
-        None
+/// Moves with which `side` forces a standard win within `moves` of its own
+/// moves, however the opponent answers.
+pub fn winning_moves(elements: &mut [Cell], side: Player, moves: usize) -> Vec<usize> {
+    let mut winning = Vec::new();
+    if moves == 0 {
+        return winning;
     }
+
+    for i in 0..elements.len() {
+        if elements[i] == Cell::Empty {
+            elements[i] = Cell::Player(side);
+            let wins = match Game::get_game_state(elements) {
+                GameState::InProgress => all_replies_lose(elements, side.opponent(), moves - 1),
+                state => state.winner() == Some(side),
+            };
+            elements[i] = Cell::Empty;
+            if wins {
+                winning.push(i);
+            }
+        }
+    }
+    winning
+}
+
+/// Whether every move of `side` still leaves its opponent a win within `moves`.
+fn all_replies_lose(elements: &mut [Cell], side: Player, moves: usize) -> bool {
+    for i in 0..elements.len() {
+        if elements[i] == Cell::Empty {
+            elements[i] = Cell::Player(side);
+            let loses = Game::get_game_state(elements) == GameState::InProgress
+                && !winning_moves(elements, side.opponent(), moves).is_empty();
+            elements[i] = Cell::Empty;
+            if !loses {
+                return false;
+            }
+        }
+    }
+    true
 }
 
 pub fn make_best_move(game: &mut Game) {
-    let mut cells = game.get_cells();
-    let best_move = get_best_move(&mut cells);
-    if let Some(id) = best_move {
+    let cells = game.get_cells();
+    let rules = game.rules();
+    let lines = game.lines().to_vec();
+    let moves = game.moves().to_vec();
+    let first = game.first();
+    let choice = match rules {
+        RuleSet::Vanishing => game.ai_mut().choose_vanishing_move(&moves, first),
+        _ => game
+            .ai_mut()
+            .choose_move(&cells, Player::Player1, rules, &lines),
+    };
+    if let Some(id) = choice {
         game.make_move(id, Player::Player1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(layout: &str) -> Vec<Cell> {
+        layout
+            .chars()
+            .map(|c| match c {
+                'O' => Cell::Player(Player::Player1),
+                'X' => Cell::Player(Player::Player2),
+                '*' => Cell::Blocked,
+                _ => Cell::Empty,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn takes_immediate_win() {
+        // Player1 can win at 2 now, or fork and win later.
+        let mut cells = board("OO.X.X..X");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            vec![2]
+        );
+        // Even without time for more than one ply.
+        let lines = Topology::Flat.lines();
+        assert_eq!(
+            get_best_moves_until(&mut cells, RuleSet::Standard, lines, Instant::now()),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn delays_forced_loss() {
+        // Blocking at 8 only postpones the fork at 6, but every other move
+        // loses on the very next ply.
+        let mut cells = board("XO..X....");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            vec![8]
+        );
+    }
+
+    #[test]
+    fn tie_break_policies() {
+        let moves = [0, 2, 4, 6, 8];
+        assert_eq!(TieBreak::First.pick(&moves), Some(0));
+        assert_eq!(TieBreak::CenterFirst.pick(&moves), Some(4));
+        assert_eq!(TieBreak::CenterFirst.pick(&[1, 3, 8]), Some(8));
+
+        let mut a = TieBreak::Random(Rng::new(7));
+        let mut b = TieBreak::Random(Rng::new(7));
+        for _ in 0..10 {
+            assert_eq!(a.pick(&moves), b.pick(&moves));
+        }
+    }
+
+    #[test]
+    fn book_moves_follow_the_tie_break() {
+        let cells = board(".........");
+        let lines = Topology::Flat.lines();
+        let mut first = Ai::new(TieBreak::First, 1.0);
+        assert_eq!(
+            first.choose_move(&cells, Player::Player1, RuleSet::Standard, lines),
+            opening_book::global().heaviest(&cells, Player::Player1)
+        );
+
+        let mut a = Ai::new(TieBreak::Random(Rng::new(3)), 1.0);
+        let mut b = Ai::new(TieBreak::Random(Rng::new(3)), 1.0);
+        for _ in 0..10 {
+            assert_eq!(
+                a.choose_move(&cells, Player::Player1, RuleSet::Standard, lines),
+                b.choose_move(&cells, Player::Player1, RuleSet::Standard, lines)
+            );
+        }
+    }
+
+    #[test]
+    fn minimax_agrees_with_tablebase() {
+        let mut cells = board("X...O...X");
+        let mut from_tablebase = tablebase::global().best_moves(&cells, Player::Player1);
+        from_tablebase.sort_unstable();
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            from_tablebase
+        );
+    }
+
+    #[test]
+    fn torus_lines_wrap() {
+        assert_eq!(Topology::Flat.lines().len(), 8);
+        assert_eq!(Topology::Torus.lines().len(), 12);
+        // A diagonal through the right edge: b1, c2, a3.
+        let cells = board(".O...OO..");
+        assert_eq!(
+            Game::get_game_state_on(&cells, Topology::Flat.lines()),
+            GameState::InProgress
+        );
+        assert_eq!(
+            Game::get_game_state_on(&cells, Topology::Torus.lines()),
+            GameState::GameWon {
+                player: Player::Player1,
+                cells: vec![1, 5, 6]
+            }
+        );
+        // Player1 finishes the wrapped diagonal rather than block at 3.
+        let mut cells = board(".OX..O.X.");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Torus.lines()),
+            vec![6]
+        );
+    }
+
+    #[test]
+    fn blocked_center_kills_lines() {
+        // With the center open Player1 wins on the diagonal; blocked, every
+        // line is dead and all moves draw.
+        let mut cells = board("O.X...X.O");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            vec![4]
+        );
+        let mut cells = board("O.X.*.X.O");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            vec![1, 3, 5, 7]
+        );
+    }
+
+    #[test]
+    fn forced_win_needs_enough_moves() {
+        // After an opposite corner reply, X wins from the other corners but
+        // only on its third move.
+        let mut cells = board("X.......O");
+        assert!(winning_moves(&mut cells, Player::Player2, 2).is_empty());
+        assert_eq!(winning_moves(&mut cells, Player::Player2, 3), vec![2, 6]);
+        let mut cells = board("X.O.X.O..");
+        assert_eq!(winning_moves(&mut cells, Player::Player2, 1), vec![8]);
+    }
+
+    #[test]
+    fn difficulty_limits_search() {
+        // Player2 threatens the bottom row; only medium and hard block it.
+        let cells = board("....O..XX");
+        let lines = Topology::Flat.lines();
+        let mut ai = Ai::new(TieBreak::First, 0.0);
+        for &(difficulty, blocks) in &[
+            (Difficulty::Easy, false),
+            (Difficulty::Medium, true),
+            (Difficulty::Hard, true),
+        ] {
+            ai.difficulty = difficulty;
+            let choice = ai.choose_move(&cells, Player::Player1, RuleSet::Standard, lines);
+            assert_eq!(choice == Some(6), blocks, "{}", difficulty);
+        }
+    }
+
+    #[test]
+    fn misere_avoids_own_line() {
+        // Completing the top row would win normally but loses under misère.
+        let mut cells = board("OO.XX.X..");
+        assert_eq!(
+            get_best_moves(&mut cells, RuleSet::Standard, Topology::Flat.lines()),
+            vec![2]
+        );
+        assert!(!get_best_moves(&mut cells, RuleSet::Misere, Topology::Flat.lines()).contains(&2));
+        assert_eq!(
+            RuleSet::Misere.game_state(&board("OOOXX.X.."), Topology::Flat.lines()),
+            GameState::GameLost {
+                player: Player::Player1,
+                cells: vec![0, 1, 2]
+            }
+        );
+    }
+}
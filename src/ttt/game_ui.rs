@@ -1,33 +1,36 @@
+use std::time::Duration;
+
 use super::draw_helpers::*;
 
 use ggez::{
-    event::{self, KeyCode, KeyMods, MouseButton},
-    graphics::{self, DrawParam, MeshBuilder},
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
     Context, GameResult,
 };
 
 use super::game::{Cell, ChangeSelected, FieldType, Game, GameState, Player, SelectedCell};
 
-use super::config::PLAY_FIELD_SIZE;
-use super::game_logic;
+use super::app::Mode;
+use super::config::{CLOCK_POS, PLAY_FIELD_SIZE};
+use super::series::Series;
 
-impl event::EventHandler for Game {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+impl Mode for Game {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.tick_clock(ggez::timer::delta(ctx));
+        self.animate(ggez::timer::delta(ctx));
         Ok(())
     }
 
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
         if button == MouseButton::Left {
-            let field_type = Game::get_field_type(x, y);
+            let field_type = self.get_shape_field_type(x, y);
             if field_type == FieldType::PlayField {
-                let cells = self.get_cells();
-                let game_state = Game::get_game_state(&cells);
+                let game_state = self.get_state();
                 if game_state == GameState::InProgress {
                     let cell = Game::get_cell(x, y);
-                    let success =
-                        self.set_cell_state(cell.0, cell.1, Cell::Player(Player::Player2));
+                    let success = self.set_cell_state(cell.0, cell.1, Player::Player2);
                     if success {
-                        game_logic::make_best_move(self);
+                        self.answer_human_move();
                     }
                 }
             }
@@ -35,7 +38,7 @@ impl event::EventHandler for Game {
     }
 
     fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
-        let field_type = Game::get_field_type(x, y);
+        let field_type = self.get_shape_field_type(x, y);
         if field_type == FieldType::PlayField {
             self.select_cell(x, y);
         }
@@ -49,7 +52,7 @@ impl event::EventHandler for Game {
         _repeat: bool,
     ) {
         match keycode {
-            KeyCode::R => self.clear(),
+            KeyCode::R => self.restart(),
             KeyCode::Left => self.move_selected_cell(ChangeSelected::Left),
             KeyCode::Right => self.move_selected_cell(ChangeSelected::Right),
             KeyCode::Up => self.move_selected_cell(ChangeSelected::Up),
@@ -59,7 +62,7 @@ impl event::EventHandler for Game {
                 if game_state == GameState::InProgress {
                     let success = self.make_move_on_selected_cell(Player::Player2);
                     if success {
-                        game_logic::make_best_move(self);
+                        self.answer_human_move();
                     }
                 }
             }
@@ -67,46 +70,106 @@ impl event::EventHandler for Game {
         }
     }
 
-    fn draw(&mut self, _ctx: &mut Context) -> GameResult {
-        graphics::clear(_ctx, graphics::Color::from_rgb_u32(0xB0B0B0));
+    fn draw(&mut self, _ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(_ctx);
 
         let mb = &mut MeshBuilder::new();
 
-        draw_field(mb);
-
+        painter.draw_field(mb, self.shape().blocked());
+
+        let animation = self.animation();
+        let (cleared, clearing) = animation.cleared();
+        for &(id, player) in cleared {
+            painter.draw_clearing_mark(
+                mb,
+                player,
+                id % PLAY_FIELD_SIZE,
+                id / PLAY_FIELD_SIZE,
+                clearing,
+            );
+        }
         for i in 0..PLAY_FIELD_SIZE {
             for j in 0..PLAY_FIELD_SIZE {
                 let cell_state = self.get_cell_state(i, j);
                 if let Cell::Player(player) = cell_state {
-                    draw_player(mb, player, i, j);
+                    let id = i + j * PLAY_FIELD_SIZE;
+                    if self.oldest_mark(player) == Some(id) {
+                        painter.draw_oldest_mark(mb, player, i, j);
+                    } else {
+                        painter.draw_player(mb, player, i, j, animation.mark(id));
+                    }
                 }
             }
         }
 
-        let cells = self.get_cells();
-        let game_state = Game::get_game_state(&cells);
+        let game_state = self.get_state();
         match &game_state {
-            GameState::GameWon { player: _, cells } => {
-                draw_red_line(mb, cells[0], cells[2]);
+            GameState::GameWon { cells, .. } | GameState::GameLost { cells, .. } => {
+                painter.draw_red_line(mb, cells, self.topology(), animation.win_line());
             }
             GameState::InProgress => {
                 if let SelectedCell::Selected { x, y } = self.get_selected_cell() {
-                    draw_selected_cell(mb, x, y);
+                    painter.draw_selected_cell(mb, x, y);
                 }
             }
             _ => (),
         }
 
-        let text = game_state_to_str(&game_state);
-        draw_text(_ctx, &text);
+        let mut text = game_state_to_str(&game_state);
+        if let Some(series) = self.series() {
+            text += &format!("\n{}", series_to_str(series));
+        }
+        if let Some((name, rating)) = self.player_rating() {
+            text += &format!("\n{} rating: {:.0}", name, rating.elo);
+        }
+        painter.draw_text(_ctx, &text);
+        if let Some(clock) = self.clock() {
+            let clocks = format!(
+                "Computer {}\nPlayer {}",
+                format_clock(clock.remaining(Player::Player1)),
+                format_clock(clock.remaining(Player::Player2))
+            );
+            painter.draw_text_at(_ctx, &clocks, CLOCK_POS);
+        }
+        painter.draw_sprites(_ctx)?;
         let mbb = mb.build(_ctx)?;
         ggez::graphics::draw(_ctx, &mbb, DrawParam::default())?;
 
-        graphics::present(_ctx)?;
-
-        ggez::timer::yield_now();
         Ok(())
     }
+
+    fn result(&self) -> Option<String> {
+        let mut text = finished_to_str(&self.get_state())?;
+        if let Some(series) = self.series() {
+            text += &format!("\n{}", series_to_str(series));
+        }
+        Some(text)
+    }
+
+    fn is_animating(&self) -> bool {
+        Game::is_animating(self)
+    }
+
+    fn restart(&mut self) {
+        Game::restart(self);
+    }
+}
+
+/// What the game-over screen says about a finished game, `None` while it's
+/// in progress.
+pub(crate) fn finished_to_str(game_state: &GameState) -> Option<String> {
+    match game_state {
+        GameState::InProgress => None,
+        game_state => Some(game_state_to_str(game_state)),
+    }
+}
+
+/// The status line under a board, `in_progress` while the game is on.
+pub(crate) fn status_to_str(game_state: &GameState, in_progress: &str) -> String {
+    match game_state {
+        GameState::InProgress => String::from(in_progress),
+        game_state => game_state_to_str(game_state),
+    }
 }
 
 fn game_state_to_str(game_state: &GameState) -> String {
@@ -117,5 +180,43 @@ fn game_state_to_str(game_state: &GameState) -> String {
             Player::Player1 => String::from("Computer won"),
             Player::Player2 => String::from("Player won"),
         },
+        GameState::GameLost { player, .. } => match player {
+            Player::Player1 => String::from("Computer lost"),
+            Player::Player2 => String::from("Player lost"),
+        },
+        GameState::LostOnTime { player } => match player {
+            Player::Player1 => String::from("Computer lost on time"),
+            Player::Player2 => String::from("Player lost on time"),
+        },
+    }
+}
+
+/// Running score of the match, and its result once it's decided.
+fn series_to_str(series: &Series) -> String {
+    let mut text = format!(
+        "Best of {}: Player {}, Computer {}, draws {}",
+        series.best_of(),
+        series.wins(Player::Player2),
+        series.wins(Player::Player1),
+        series.draws()
+    );
+    if series.is_over() {
+        text += match series.winner() {
+            Some(Player::Player1) => " - Computer wins the match",
+            Some(Player::Player2) => " - Player wins the match",
+            None => " - the match is drawn",
+        };
+    }
+    text
+}
+
+/// Minutes and seconds, with tenths in the last ten seconds.
+fn format_clock(remaining: Duration) -> String {
+    let seconds = remaining.as_secs_f32();
+    if seconds < 10.0 {
+        format!("0:{:04.1}", seconds)
+    } else {
+        let seconds = remaining.as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
     }
 }
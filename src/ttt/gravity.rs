@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use super::config::{GRAVITY_AI_TIME_MS, GRAVITY_DROP_SPEED};
+use super::game::{run_through, Cell, GameState, Player, DIRECTIONS};
+use super::mcts::{self, Budget};
+use super::position::Position;
+use super::rng::Rng;
+
+/// Board where a piece falls to the lowest empty cell of its column, as in
+/// Connect Four. Moves are column numbers, cells are `x + y * width` with row
+/// 0 at the top.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GravityPosition {
+    width: usize,
+    height: usize,
+    connect: usize,
+    cells: Vec<Cell>,
+    last_move: Option<usize>,
+    side: Player,
+}
+
+impl GravityPosition {
+    pub fn new(width: usize, height: usize, connect: usize, first: Player) -> Self {
+        GravityPosition {
+            width,
+            height,
+            connect,
+            cells: vec![Cell::Empty; width * height],
+            last_move: None,
+            side: first,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, id: usize) -> Cell {
+        self.cells[id]
+    }
+
+    /// Row a piece dropped into `column` comes to rest in, if there is room.
+    pub fn landing_row(&self, column: usize) -> Option<usize> {
+        (0..self.height)
+            .rev()
+            .find(|&y| self.cells[column + y * self.width] == Cell::Empty)
+    }
+
+    /// A column that completes a line of `player`, if any.
+    fn winning_column(&self, player: Player) -> Option<usize> {
+        self.legal_moves().into_iter().find(|&column| {
+            let mut position = self.clone();
+            position.side = player;
+            position.play(column);
+            position.state().winner() == Some(player)
+        })
+    }
+
+    fn winning_run(&self) -> Option<Vec<usize>> {
+        let id = self.last_move?;
+        DIRECTIONS
+            .iter()
+            .map(|&direction| run_through(&self.cells, self.width, id, direction))
+            .find(|run| run.len() >= self.connect)
+    }
+}
+
+impl Position for GravityPosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.winning_run().is_some() {
+            return Vec::new();
+        }
+        (0..self.width)
+            .filter(|&column| self.cells[column] == Cell::Empty)
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        let y = self.landing_row(mv).expect("column has room");
+        let id = mv + y * self.width;
+        self.cells[id] = Cell::Player(self.side);
+        self.last_move = Some(id);
+        self.side = self.side.opponent();
+    }
+
+    /// Only the last move can have completed a line.
+    fn state(&self) -> GameState {
+        if let Some(cells) = self.winning_run() {
+            if let Some(Cell::Player(player)) = self.last_move.map(|id| self.cells[id]) {
+                return GameState::GameWon { player, cells };
+            }
+        }
+        if self.cells.contains(&Cell::Empty) {
+            GameState::InProgress
+        } else {
+            GameState::Tie
+        }
+    }
+}
+
+/// Wins or blocks at once if possible, otherwise runs MCTS for `budget`.
+pub fn best_move(position: &GravityPosition, budget: Duration, rng: &mut Rng) -> Option<usize> {
+    if position.state() != GameState::InProgress {
+        return None;
+    }
+    let side = position.side_to_move();
+    position
+        .winning_column(side)
+        .or_else(|| position.winning_column(side.opponent()))
+        .or_else(|| mcts::search(position, Budget::Time(budget), rng))
+}
+
+/// A piece on its way down to `cell`; `row` is its current height in cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FallingPiece {
+    pub cell: usize,
+    pub player: Player,
+    pub row: f32,
+}
+
+/// Gravity tic-tac-toe against the computer; the human plays `Player2` and
+/// moves first. Moves take effect at once, `falling` only animates the last one
+/// and holds back input and the computer's reply until it has landed.
+pub struct GravityGame {
+    position: GravityPosition,
+    selected_column: Option<usize>,
+    falling: Option<FallingPiece>,
+    /// Multiplies `GRAVITY_DROP_SPEED`, 0 drops pieces at once.
+    animation_speed: f32,
+    rng: Rng,
+}
+
+impl GravityGame {
+    pub fn new(width: usize, height: usize, connect: usize) -> Self {
+        GravityGame {
+            position: GravityPosition::new(width, height, connect, Player::Player2),
+            selected_column: None,
+            falling: None,
+            animation_speed: 1.0,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let position = &self.position;
+        self.position = GravityPosition::new(
+            position.width,
+            position.height,
+            position.connect,
+            Player::Player2,
+        );
+        self.selected_column = None;
+        self.falling = None;
+    }
+
+    pub fn set_animation_speed(&mut self, speed: f32) {
+        self.animation_speed = speed;
+    }
+
+    pub(crate) fn position(&self) -> &GravityPosition {
+        &self.position
+    }
+
+    pub(crate) fn selected_column(&self) -> Option<usize> {
+        self.selected_column
+    }
+
+    pub(crate) fn falling(&self) -> Option<FallingPiece> {
+        self.falling
+    }
+
+    pub(crate) fn select_column(&mut self, column: usize) {
+        self.selected_column = Some(column.min(self.position.width - 1));
+    }
+
+    /// Only columns can be selected, so up and down are ignored.
+    pub(crate) fn move_selected(&mut self, dx: isize) {
+        let column = match self.selected_column {
+            Some(column) => column as isize + dx,
+            None => (self.position.width / 2) as isize,
+        };
+        self.select_column(column.max(0) as usize);
+    }
+
+    fn start_drop(&mut self, column: usize) {
+        let player = self.position.side_to_move();
+        if let Some(y) = self.position.landing_row(column) {
+            self.position.play(column);
+            self.falling = Some(FallingPiece {
+                cell: column + y * self.position.width,
+                player,
+                row: 0.0,
+            });
+        }
+    }
+
+    pub(crate) fn play_column(&mut self, column: usize) -> bool {
+        if self.falling.is_some()
+            || self.position.side_to_move() != Player::Player2
+            || !self.position.legal_moves().contains(&column)
+        {
+            return false;
+        }
+        self.start_drop(column);
+        true
+    }
+
+    /// Moves the falling piece on by `dt` seconds; once it lands the computer
+    /// answers with a drop of its own.
+    pub(crate) fn advance(&mut self, dt: f32) {
+        let width = self.position.width;
+        if let Some(piece) = &mut self.falling {
+            piece.row += dt * GRAVITY_DROP_SPEED * self.animation_speed;
+            if self.animation_speed > 0.0 && piece.row < (piece.cell / width) as f32 {
+                return;
+            }
+            self.falling = None;
+        }
+        if self.position.side_to_move() == Player::Player1 {
+            let budget = Duration::from_millis(GRAVITY_AI_TIME_MS);
+            if let Some(reply) = best_move(&self.position, budget, &mut self.rng) {
+                self.start_drop(reply);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_stack_in_a_column() {
+        let mut position = GravityPosition::new(4, 3, 3, Player::Player1);
+        position.play(1);
+        position.play(1);
+        assert_eq!(position.cell(1 + 2 * 4), Cell::Player(Player::Player1));
+        assert_eq!(position.cell(1 + 4), Cell::Player(Player::Player2));
+        position.play(1);
+        assert!(!position.legal_moves().contains(&1));
+    }
+
+    #[test]
+    fn four_in_a_row_wins() {
+        let mut position = GravityPosition::new(7, 6, 4, Player::Player1);
+        for &column in &[0, 0, 1, 1, 2, 2, 3] {
+            position.play(column);
+        }
+        assert_eq!(
+            position.state(),
+            GameState::GameWon {
+                player: Player::Player1,
+                cells: vec![35, 36, 37, 38]
+            }
+        );
+        assert!(position.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn blocks_open_line() {
+        let mut position = GravityPosition::new(7, 6, 4, Player::Player2);
+        for &column in &[2, 2, 3, 3, 4] {
+            position.play(column);
+        }
+        let mv = best_move(&position, Duration::from_millis(10), &mut Rng::new(1));
+        assert!(mv == Some(1) || mv == Some(5));
+    }
+}
@@ -0,0 +1,153 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    timer, Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{GRAVITY_SQUARE_SIZE, SCREEN_SIZE};
+use super::game::{Cell, GameState};
+use super::game_ui::{finished_to_str, status_to_str};
+use super::gravity::{GravityGame, GravityPosition};
+use super::position::Position;
+
+/// Top left corner and square size that fit the board into the window.
+fn layout(position: &GravityPosition) -> ((f32, f32), f32) {
+    let (width, height) = (position.width() as f32, position.height() as f32);
+    let square_size = GRAVITY_SQUARE_SIZE
+        .min(SCREEN_SIZE.0 * 0.9 / width)
+        .min(SCREEN_SIZE.1 * 0.8 / height);
+    let pos = (
+        (SCREEN_SIZE.0 - square_size * width) / 2.0,
+        (SCREEN_SIZE.1 * 0.9 - square_size * height) / 2.0,
+    );
+    (pos, square_size)
+}
+
+/// Column under the mouse; clicking anywhere above or on a column drops into it.
+fn get_cell(position: &GravityPosition, x: f32, y: f32) -> Option<usize> {
+    let (pos, square_size) = layout(position);
+    let column = (x - pos.0) / square_size;
+    let bottom = pos.1 + square_size * position.height() as f32;
+    if (0.0..position.width() as f32).contains(&column) && y < bottom {
+        Some(column as usize)
+    } else {
+        None
+    }
+}
+
+impl Mode for GravityGame {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.advance(timer::delta(ctx).as_secs_f32());
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left {
+            if let Some(column) = get_cell(self.position(), x, y) {
+                self.play_column(column);
+            }
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some(column) = get_cell(self.position(), x, y) {
+            self.select_column(column);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::Left => self.move_selected(-1),
+            KeyCode::Right => self.move_selected(1),
+            KeyCode::Space | KeyCode::Down => {
+                if let Some(column) = self.selected_column() {
+                    self.play_column(column);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+        let (width, height) = (position.width(), position.height());
+        let (pos, square_size) = layout(position);
+        let falling = self.falling();
+
+        painter.draw_board_grid(mb, pos, square_size, (width, height), 3.0);
+        for id in 0..width * height {
+            if falling.is_some_and(|piece| piece.cell == id) {
+                continue;
+            }
+            if let Cell::Player(player) = position.cell(id) {
+                painter.draw_player_at(mb, pos, square_size, player, id % width, id / width);
+            }
+        }
+
+        let game_state = position.state();
+        if let Some(piece) = falling {
+            let piece_pos = (pos.0, pos.1 + piece.row * square_size);
+            painter.draw_player_at(
+                mb,
+                piece_pos,
+                square_size,
+                piece.player,
+                piece.cell % width,
+                0,
+            );
+        } else {
+            match &game_state {
+                GameState::GameWon { cells, .. } => {
+                    let last = cells[cells.len() - 1];
+                    painter.draw_red_line_at(mb, pos, square_size, width, cells[0], last);
+                }
+                GameState::InProgress => {
+                    let landing = self
+                        .selected_column()
+                        .and_then(|column| Some((column, position.landing_row(column)?)));
+                    if let Some((x, y)) = landing {
+                        let cursor_color = rgb(painter.theme().cursor);
+                        draw_frame(mb, pos, square_size, x, y, cursor_color);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let text = if falling.is_some() {
+            String::from("In progress")
+        } else {
+            status_to_str(&game_state, "Gravity, pieces fall to the bottom")
+        };
+        painter.draw_text(ctx, &text);
+        painter.draw_sprites(ctx)?;
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        if self.falling().is_some() {
+            return None;
+        }
+        finished_to_str(&self.position().state())
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
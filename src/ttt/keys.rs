@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use ggez::event::{Axis, Button, KeyCode};
+
+use super::config::GAMEPAD_STICK_THRESHOLD;
+
+/// What the player can ask for with a key or a gamepad button.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Command {
+    Up,
+    Down,
+    Left,
+    Right,
+    Place,
+    Restart,
+    Menu,
+}
+
+impl Command {
+    pub const ALL: [Command; 7] = [
+        Command::Up,
+        Command::Down,
+        Command::Left,
+        Command::Right,
+        Command::Place,
+        Command::Restart,
+        Command::Menu,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::Up => "up",
+            Command::Down => "down",
+            Command::Left => "left",
+            Command::Right => "right",
+            Command::Place => "place",
+            Command::Restart => "restart",
+            Command::Menu => "menu",
+        }
+    }
+
+    /// The key the games and menus handle the command under.
+    pub fn key(self) -> KeyCode {
+        match self {
+            Command::Up => KeyCode::Up,
+            Command::Down => KeyCode::Down,
+            Command::Left => KeyCode::Left,
+            Command::Right => KeyCode::Right,
+            Command::Place => KeyCode::Space,
+            Command::Restart => KeyCode::R,
+            Command::Menu => KeyCode::Escape,
+        }
+    }
+}
+
+impl std::str::FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Command::ALL
+            .iter()
+            .copied()
+            .find(|command| command.name() == s)
+            .ok_or_else(|| format!("unknown command '{}'", s))
+    }
+}
+
+/// Keys that can be bound, by the names ggez gives them.
+const BINDABLE_KEYS: [KeyCode; 62] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Space,
+    KeyCode::Return,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Back,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::NumpadEnter,
+];
+
+/// `W`, `Space`, `Key1`, `F5` and so on.
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|&key| key_name(key) == name)
+        .ok_or_else(|| format!("unknown key '{}'", name))
+}
+
+/// Which keys move the cursor besides the arrow keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyPreset {
+    Arrows,
+    /// W, A, S and D.
+    Wasd,
+    /// H, J, K and L as in vi.
+    Vim,
+}
+
+impl KeyPreset {
+    pub const ALL: [KeyPreset; 3] = [KeyPreset::Arrows, KeyPreset::Wasd, KeyPreset::Vim];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyPreset::Arrows => "arrows",
+            KeyPreset::Wasd => "wasd",
+            KeyPreset::Vim => "hjkl",
+        }
+    }
+}
+
+impl std::str::FromStr for KeyPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyPreset::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| format!("unknown key preset '{}'", s))
+    }
+}
+
+impl std::fmt::Display for KeyPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The keys bound to each command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    keys: BTreeMap<Command, Vec<KeyCode>>,
+}
+
+impl KeyBindings {
+    /// Every command on its own key, plus the preset's keys for moving.
+    pub fn preset(preset: KeyPreset) -> Self {
+        let mut keys: BTreeMap<Command, Vec<KeyCode>> = Command::ALL
+            .iter()
+            .map(|&command| (command, vec![command.key()]))
+            .collect();
+        let extra: &[KeyCode] = match preset {
+            KeyPreset::Arrows => &[],
+            KeyPreset::Wasd => &[KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D],
+            KeyPreset::Vim => &[KeyCode::K, KeyCode::J, KeyCode::H, KeyCode::L],
+        };
+        let moves = [Command::Up, Command::Down, Command::Left, Command::Right];
+        for (command, &key) in moves.iter().zip(extra.iter()) {
+            keys.entry(*command).or_default().push(key);
+        }
+        KeyBindings { keys }
+    }
+
+    /// Binds `command` to `keys` instead of what it had, taking them away
+    /// from any other command.
+    pub fn bind(&mut self, command: Command, keys: Vec<KeyCode>) {
+        for bound in self.keys.values_mut() {
+            bound.retain(|key| !keys.contains(key));
+        }
+        self.keys.insert(command, keys);
+    }
+
+    pub fn keys(&self, command: Command) -> &[KeyCode] {
+        self.keys.get(&command).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn command(&self, key: KeyCode) -> Option<Command> {
+        Command::ALL
+            .iter()
+            .copied()
+            .find(|&command| self.keys(command).contains(&key))
+    }
+
+    /// The key the games handle for what `key` is bound to. A command's own
+    /// key that has been bound away does nothing, other keys stay as they
+    /// are for the variants that use them.
+    pub fn translate(&self, key: KeyCode) -> Option<KeyCode> {
+        match self.command(key) {
+            Some(command) => Some(command.key()),
+            None if Command::ALL.iter().any(|command| command.key() == key) => None,
+            None => Some(key),
+        }
+    }
+}
+
+pub fn gamepad_command(button: Button) -> Option<Command> {
+    match button {
+        Button::DPadUp => Some(Command::Up),
+        Button::DPadDown => Some(Command::Down),
+        Button::DPadLeft => Some(Command::Left),
+        Button::DPadRight => Some(Command::Right),
+        Button::South => Some(Command::Place),
+        Button::North | Button::West => Some(Command::Restart),
+        Button::East | Button::Start => Some(Command::Menu),
+        _ => None,
+    }
+}
+
+/// Turns the left stick into cursor moves, one each time it is pushed past
+/// the threshold.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Stick {
+    /// The direction each axis is pushed in, -1, 0 or 1.
+    pushed: [i8; 2],
+}
+
+impl Stick {
+    pub fn moved(&mut self, axis: Axis, value: f32) -> Option<Command> {
+        let (index, commands) = match axis {
+            Axis::LeftStickX => (0, [Command::Left, Command::Right]),
+            // Up is positive.
+            Axis::LeftStickY => (1, [Command::Down, Command::Up]),
+            _ => return None,
+        };
+        let pushed = if value > GAMEPAD_STICK_THRESHOLD {
+            1
+        } else if value < -GAMEPAD_STICK_THRESHOLD {
+            -1
+        } else {
+            0
+        };
+        let before = std::mem::replace(&mut self.pushed[index], pushed);
+        match pushed {
+            1 if before != 1 => Some(commands[1]),
+            -1 if before != -1 => Some(commands[0]),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_translate_to_the_games_keys() {
+        let mut bindings = KeyBindings::preset(KeyPreset::Vim);
+        assert_eq!(bindings.translate(KeyCode::J), Some(KeyCode::Down));
+        assert_eq!(bindings.translate(KeyCode::Up), Some(KeyCode::Up));
+        assert_eq!(bindings.translate(KeyCode::PageUp), Some(KeyCode::PageUp));
+
+        bindings.bind(Command::Place, vec![parse_key("Return").unwrap()]);
+        assert_eq!(bindings.translate(KeyCode::Return), Some(KeyCode::Space));
+        assert_eq!(bindings.translate(KeyCode::Space), None);
+        assert!(parse_key("Hyper").is_err());
+
+        let mut wasd = KeyBindings::preset(KeyPreset::Wasd);
+        wasd.bind(Command::Place, vec![KeyCode::W]);
+        assert_eq!(wasd.translate(KeyCode::W), Some(KeyCode::Space));
+        assert_eq!(wasd.keys(Command::Up), &[KeyCode::Up]);
+
+        let mut stick = Stick::default();
+        assert_eq!(stick.moved(Axis::LeftStickY, 0.9), Some(Command::Up));
+        assert_eq!(stick.moved(Axis::LeftStickY, 0.95), None);
+        assert_eq!(stick.moved(Axis::LeftStickY, 0.0), None);
+        assert_eq!(stick.moved(Axis::LeftStickX, -0.8), Some(Command::Left));
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::{Duration, Instant};
+
+use super::engine::Engine;
+use super::game::{Cell, GameState, Player};
+use super::position::{Classic, Position};
+use super::rng::Rng;
+
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// How long a search may run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+struct Node {
+    mv: Option<usize>,
+    parent: Option<usize>,
+    /// Player who made the move leading to this node.
+    mover: Player,
+    children: Vec<usize>,
+    untried: Vec<usize>,
+    visits: u32,
+    /// Sum of results from the point of view of `mover`: 1 win, 0.5 draw.
+    reward: f64,
+}
+
+fn select_child(nodes: &[Node], node: usize) -> usize {
+    let parent_visits = f64::from(nodes[node].visits).ln();
+    let uct = |&child: &usize| {
+        let child = &nodes[child];
+        let visits = f64::from(child.visits);
+        child.reward / visits + EXPLORATION * (parent_visits / visits).sqrt()
+    };
+    *nodes[node]
+        .children
+        .iter()
+        .max_by(|a, b| uct(a).total_cmp(&uct(b)))
+        .expect("fully expanded node has children")
+}
+
+fn winner<P: Position>(position: &P) -> Option<Option<Player>> {
+    match position.state() {
+        GameState::InProgress => None,
+        state => Some(state.winner()),
+    }
+}
+
+fn playout<P: Position>(position: &mut P, rng: &mut Rng) -> Option<Player> {
+    loop {
+        if let Some(winner) = winner(position) {
+            return winner;
+        }
+        let moves = position.legal_moves();
+        position.play(moves[rng.below(moves.len())]);
+    }
+}
+
+/// Monte Carlo tree search with UCT selection and random playouts, returning
+/// the most visited move once the budget is used up.
+pub fn search<P: Position>(root: &P, budget: Budget, rng: &mut Rng) -> Option<usize> {
+    if winner(root).is_some() {
+        return None;
+    }
+
+    let mut nodes = vec![Node {
+        mv: None,
+        parent: None,
+        mover: root.side_to_move().opponent(),
+        children: Vec::new(),
+        untried: root.legal_moves(),
+        visits: 0,
+        reward: 0.0,
+    }];
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    loop {
+        let done = match budget {
+            Budget::Iterations(limit) => iterations >= limit.max(1),
+            Budget::Time(limit) => iterations > 0 && start.elapsed() >= limit,
+        };
+        if done {
+            break;
+        }
+        iterations += 1;
+
+        let mut position = root.clone();
+        let mut node = 0;
+
+        // Selection.
+        while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+            node = select_child(&nodes, node);
+            position.play(nodes[node].mv.expect("child node has a move"));
+        }
+
+        // Expansion.
+        if !nodes[node].untried.is_empty() && winner(&position).is_none() {
+            let pick = rng.below(nodes[node].untried.len());
+            let mv = nodes[node].untried.swap_remove(pick);
+            let mover = position.side_to_move();
+            position.play(mv);
+            let child = nodes.len();
+            nodes.push(Node {
+                mv: Some(mv),
+                parent: Some(node),
+                mover,
+                children: Vec::new(),
+                untried: position.legal_moves(),
+                visits: 0,
+                reward: 0.0,
+            });
+            nodes[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation.
+        let winner = playout(&mut position, rng);
+
+        // Backpropagation.
+        let mut current = Some(node);
+        while let Some(id) = current {
+            let n = &mut nodes[id];
+            n.visits += 1;
+            n.reward += match winner {
+                Some(player) if player == n.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = n.parent;
+        }
+    }
+
+    nodes[0]
+        .children
+        .iter()
+        .max_by_key(|&&child| nodes[child].visits)
+        .and_then(|&child| nodes[child].mv)
+}
+
+/// MCTS player for the classic board.
+pub struct MctsEngine {
+    iterations: usize,
+    rng: Rng,
+}
+
+impl MctsEngine {
+    pub fn new(iterations: usize, rng: Rng) -> Self {
+        MctsEngine { iterations, rng }
+    }
+}
+
+impl Engine for MctsEngine {
+    fn name(&self) -> String {
+        format!("mcts:{}", self.iterations)
+    }
+
+    fn choose_move(&mut self, cells: &[Cell], side: Player) -> Option<usize> {
+        let position = Classic {
+            cells: cells.to_vec(),
+            side,
+        };
+        search(
+            &position,
+            Budget::Iterations(self.iterations),
+            &mut self.rng,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_immediate_win() {
+        let mut cells = vec![Cell::Empty; 9];
+        cells[0] = Cell::Player(Player::Player1);
+        cells[1] = Cell::Player(Player::Player1);
+        cells[3] = Cell::Player(Player::Player2);
+        cells[4] = Cell::Player(Player::Player2);
+        let mut engine = MctsEngine::new(2000, Rng::new(5));
+        assert_eq!(engine.choose_move(&cells, Player::Player1), Some(2));
+    }
+}
@@ -1,5 +1,43 @@
+pub mod animation;
+pub mod app;
+mod app_ui;
+pub mod cli;
+pub mod clock;
 pub mod config;
 mod draw_helpers;
+pub mod engine;
+mod external_engine;
 pub mod game;
-mod game_logic;
+pub mod game_logic;
 mod game_ui;
+pub mod gravity;
+mod gravity_ui;
+pub mod keys;
+mod mcts;
+pub mod multiplayer;
+mod multiplayer_ui;
+pub mod numerical;
+mod numerical_ui;
+pub mod opening_book;
+pub mod position;
+pub mod puzzle;
+mod puzzle_ui;
+pub mod qubic;
+mod qubic_ui;
+pub mod rating;
+pub mod record;
+pub mod rng;
+pub mod series;
+pub mod settings;
+pub mod shape;
+mod skin;
+mod sound;
+pub mod stats;
+pub mod tablebase;
+pub mod theme;
+pub mod tournament;
+pub mod ultimate;
+mod ultimate_ui;
+pub mod vanishing;
+pub mod wild;
+mod wild_ui;
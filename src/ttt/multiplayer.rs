@@ -0,0 +1,303 @@
+use super::config::MULTI_AI_DEPTH;
+use super::game::{run_through, DIRECTIONS};
+use super::rng::Rng;
+
+/// Most players a game can seat; each has its own symbol and colour.
+pub const MAX_PLAYERS: usize = 4;
+
+const WIN_SCORE: i32 = 1_000_000;
+
+/// Outcome of a game between any number of players, who are numbered from 0.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultiState {
+    Won { seat: usize, cells: Vec<usize> },
+    Tie,
+    InProgress,
+}
+
+/// Board for three or four players taking turns in seat order; `connect` in
+/// a row wins. `Player` only knows two sides, so seats are plain numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiPosition {
+    width: usize,
+    height: usize,
+    connect: usize,
+    players: usize,
+    cells: Vec<Option<usize>>,
+    side: usize,
+    last_move: Option<usize>,
+}
+
+impl MultiPosition {
+    pub fn new(width: usize, height: usize, connect: usize, players: usize) -> Self {
+        MultiPosition {
+            width,
+            height,
+            connect,
+            players,
+            cells: vec![None; width * height],
+            side: 0,
+            last_move: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn side_to_move(&self) -> usize {
+        self.side
+    }
+
+    pub fn cell(&self, id: usize) -> Option<usize> {
+        self.cells[id]
+    }
+
+    pub fn legal_moves(&self) -> Vec<usize> {
+        if self.state() != MultiState::InProgress {
+            return Vec::new();
+        }
+        (0..self.cells.len())
+            .filter(|&id| self.cells[id].is_none())
+            .collect()
+    }
+
+    pub fn play(&mut self, id: usize) {
+        self.cells[id] = Some(self.side);
+        self.last_move = Some(id);
+        self.side = (self.side + 1) % self.players;
+    }
+
+    /// Only the last move can have completed a line.
+    pub fn state(&self) -> MultiState {
+        if let Some(id) = self.last_move {
+            let line = DIRECTIONS
+                .iter()
+                .map(|&direction| run_through(&self.cells, self.width, id, direction))
+                .find(|run| run.len() >= self.connect);
+            if let (Some(cells), Some(seat)) = (line, self.cells[id]) {
+                return MultiState::Won { seat, cells };
+            }
+        }
+        if self.cells.contains(&None) {
+            MultiState::InProgress
+        } else {
+            MultiState::Tie
+        }
+    }
+
+    /// A cell that completes a line of `seat`, if any.
+    fn winning_cell(&self, seat: usize) -> Option<usize> {
+        self.legal_moves().into_iter().find(|&id| {
+            let mut position = self.clone();
+            position.side = seat;
+            position.play(id);
+            matches!(position.state(), MultiState::Won { .. })
+        })
+    }
+
+    /// Every stretch of `connect` cells held by one seat only counts for
+    /// that seat, more so the fuller it is. The score is `seat`'s total
+    /// less everybody else's.
+    fn evaluate(&self, seat: usize) -> i32 {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let reach = self.connect as isize - 1;
+        let mut score = 0;
+        for id in 0..self.cells.len() {
+            let (x, y) = (id as isize % width, id as isize / width);
+            for &(dx, dy) in &DIRECTIONS {
+                let (end_x, end_y) = (x + dx * reach, y + dy * reach);
+                if !(0..width).contains(&end_x) || !(0..height).contains(&end_y) {
+                    continue;
+                }
+                let mut owner = None;
+                let mut count = 0;
+                let mut contested = false;
+                for i in 0..=reach {
+                    if let Some(other) = self.cells[((x + dx * i) + (y + dy * i) * width) as usize]
+                    {
+                        contested |= owner.is_some_and(|owner| owner != other);
+                        owner = Some(other);
+                        count += 1;
+                    }
+                }
+                if let (Some(owner), false) = (owner, contested) {
+                    score += if owner == seat {
+                        count * count
+                    } else {
+                        -count * count
+                    };
+                }
+            }
+        }
+        score
+    }
+}
+
+/// Paranoid search: everybody else is assumed to play against `root`, which
+/// turns the game back into two-sided minimax with alpha-beta pruning.
+fn paranoid(
+    position: &MultiPosition,
+    root: usize,
+    depth: usize,
+    mut alpha: i32,
+    mut beta: i32,
+) -> i32 {
+    match position.state() {
+        MultiState::Won { seat, .. } => {
+            // Wins found with more depth left are sooner.
+            let score = WIN_SCORE + depth as i32;
+            return if seat == root { score } else { -score };
+        }
+        MultiState::Tie => return 0,
+        MultiState::InProgress => (),
+    }
+    if depth == 0 {
+        return position.evaluate(root);
+    }
+
+    let maximizing = position.side == root;
+    for id in position.legal_moves() {
+        let mut child = position.clone();
+        child.play(id);
+        let score = paranoid(&child, root, depth - 1, alpha, beta);
+        if maximizing {
+            alpha = alpha.max(score);
+        } else {
+            beta = beta.min(score);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    if maximizing {
+        alpha
+    } else {
+        beta
+    }
+}
+
+/// Wins at once if possible, otherwise searches `MULTI_AI_DEPTH` plies with
+/// the paranoid assumption. Equally good moves are picked at random.
+pub fn best_move(position: &MultiPosition, rng: &mut Rng) -> Option<usize> {
+    let side = position.side;
+    if let Some(id) = position.winning_cell(side) {
+        return Some(id);
+    }
+
+    let mut best_score = i32::MIN;
+    let mut best_moves = Vec::new();
+    for id in position.legal_moves() {
+        let mut child = position.clone();
+        child.play(id);
+        let score = paranoid(&child, side, MULTI_AI_DEPTH - 1, i32::MIN, i32::MAX);
+        if score > best_score {
+            best_score = score;
+            best_moves.clear();
+        }
+        if score == best_score {
+            best_moves.push(id);
+        }
+    }
+    if best_moves.is_empty() {
+        None
+    } else {
+        Some(best_moves[rng.below(best_moves.len())])
+    }
+}
+
+/// The human sits in seat 0 and moves first, the computer plays every other
+/// seat.
+pub struct MultiGame {
+    position: MultiPosition,
+    selected: Option<(usize, usize)>,
+    rng: Rng,
+}
+
+impl MultiGame {
+    pub fn new(width: usize, height: usize, connect: usize, players: usize) -> Self {
+        MultiGame {
+            position: MultiPosition::new(width, height, connect, players),
+            selected: None,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let position = &self.position;
+        self.position = MultiPosition::new(
+            position.width,
+            position.height,
+            position.connect,
+            position.players,
+        );
+        self.selected = None;
+    }
+
+    pub(crate) fn position(&self) -> &MultiPosition {
+        &self.position
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, x: usize, y: usize) {
+        let (width, height) = (self.position.width, self.position.height);
+        self.selected = Some((x.min(width - 1), y.min(height - 1)));
+    }
+
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected.unwrap_or((0, 0));
+        let x = (x as isize + dx).max(0) as usize;
+        let y = (y as isize + dy).max(0) as usize;
+        self.select(x, y);
+    }
+
+    /// Plays the human move, then lets the computer move for every other seat.
+    pub(crate) fn play_at(&mut self, x: usize, y: usize) -> bool {
+        let id = x + y * self.position.width;
+        if self.position.side != 0 || !self.position.legal_moves().contains(&id) {
+            return false;
+        }
+        self.position.play(id);
+        while self.position.side != 0 {
+            match best_move(&self.position, &mut self.rng) {
+                Some(reply) => self.position.play(reply),
+                None => break,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_rotate_through_every_seat() {
+        let mut position = MultiPosition::new(6, 6, 3, 3);
+        for (i, &id) in [0, 1, 2, 3].iter().enumerate() {
+            assert_eq!(position.side_to_move(), i % 3);
+            position.play(id);
+        }
+        assert_eq!(position.cell(3), Some(0));
+        assert_eq!(position.state(), MultiState::InProgress);
+    }
+
+    #[test]
+    fn blocks_the_next_seat() {
+        // Seat 1 can finish the second column at 19 on its next move.
+        let mut position = MultiPosition::new(6, 6, 3, 3);
+        position.cells[7] = Some(1);
+        position.cells[13] = Some(1);
+        position.cells[1] = Some(2);
+        position.cells[35] = Some(0);
+        assert_eq!(best_move(&position, &mut Rng::new(1)), Some(19));
+    }
+}
@@ -0,0 +1,140 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{MULTI_SQUARE_SIZE, SCREEN_SIZE};
+use super::multiplayer::{MultiGame, MultiPosition, MultiState};
+
+/// Top left corner and square size that fit the board into the window.
+fn layout(position: &MultiPosition) -> ((f32, f32), f32) {
+    let (width, height) = (position.width() as f32, position.height() as f32);
+    let square_size = MULTI_SQUARE_SIZE
+        .min(SCREEN_SIZE.0 * 0.9 / width)
+        .min(SCREEN_SIZE.1 * 0.8 / height);
+    let pos = (
+        (SCREEN_SIZE.0 - square_size * width) / 2.0,
+        (SCREEN_SIZE.1 * 0.9 - square_size * height) / 2.0,
+    );
+    (pos, square_size)
+}
+
+fn get_cell(position: &MultiPosition, x: f32, y: f32) -> Option<(usize, usize)> {
+    let (pos, square_size) = layout(position);
+    let cell_x = (x - pos.0) / square_size;
+    let cell_y = (y - pos.1) / square_size;
+    if (0.0..position.width() as f32).contains(&cell_x)
+        && (0.0..position.height() as f32).contains(&cell_y)
+    {
+        Some((cell_x as usize, cell_y as usize))
+    } else {
+        None
+    }
+}
+
+fn seat_name(seat: usize) -> String {
+    match seat {
+        0 => String::from("Player"),
+        seat => format!("Computer {}", seat),
+    }
+}
+
+impl Mode for MultiGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left {
+            if let Some((cell_x, cell_y)) = get_cell(self.position(), x, y) {
+                self.play_at(cell_x, cell_y);
+            }
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some((cell_x, cell_y)) = get_cell(self.position(), x, y) {
+            self.select(cell_x, cell_y);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::Left => self.move_selected(-1, 0),
+            KeyCode::Right => self.move_selected(1, 0),
+            KeyCode::Up => self.move_selected(0, -1),
+            KeyCode::Down => self.move_selected(0, 1),
+            KeyCode::Space => {
+                if let Some((x, y)) = self.selected() {
+                    self.play_at(x, y);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+        let (width, height) = (position.width(), position.height());
+        let (pos, square_size) = layout(position);
+
+        painter.draw_board_grid(mb, pos, square_size, (width, height), 3.0);
+        for id in 0..width * height {
+            if let Some(seat) = position.cell(id) {
+                painter.draw_seat(mb, pos, square_size, seat, id % width, id / width);
+            }
+        }
+
+        let game_state = position.state();
+        match &game_state {
+            MultiState::Won { cells, .. } => {
+                let last = cells[cells.len() - 1];
+                painter.draw_red_line_at(mb, pos, square_size, width, cells[0], last);
+            }
+            MultiState::InProgress => {
+                if let Some((x, y)) = self.selected() {
+                    let cursor_color = rgb(painter.theme().cursor);
+                    draw_frame(mb, pos, square_size, x, y, cursor_color);
+                }
+            }
+            _ => (),
+        }
+
+        let text = match game_state {
+            MultiState::Tie => String::from("Tie"),
+            MultiState::InProgress => String::from("In progress, you play the crosses"),
+            MultiState::Won { seat, .. } => format!("{} won", seat_name(seat)),
+        };
+        painter.draw_text(ctx, &text);
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        match self.position().state() {
+            MultiState::InProgress => None,
+            MultiState::Tie => Some(String::from("Tie")),
+            MultiState::Won { seat, .. } => Some(format!("{} won", seat_name(seat))),
+        }
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
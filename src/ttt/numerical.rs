@@ -0,0 +1,227 @@
+use super::config::{NUMERICAL_AI_ITERATIONS, PLAY_FIELD_SIZE};
+use super::game::{GameState, Player, Topology};
+use super::mcts::{self, Budget};
+use super::position::Position;
+use super::rng::Rng;
+
+const CELL_COUNT: usize = PLAY_FIELD_SIZE * PLAY_FIELD_SIZE;
+
+/// Sum a full line needs to win.
+pub const TARGET: u8 = 15;
+
+/// Moves encode both the cell and the number placed there.
+pub fn encode_move(cell: usize, number: u8) -> usize {
+    cell * 9 + usize::from(number - 1)
+}
+
+pub fn decode_move(mv: usize) -> (usize, u8) {
+    (mv / 9, (mv % 9) as u8 + 1)
+}
+
+/// Graham's numerical tic-tac-toe: the first player places the odd numbers
+/// 1 to 9, the second the even ones, each number at most once, and a full
+/// line summing to `TARGET` wins for whoever completed it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumericalPosition {
+    cells: Vec<Option<u8>>,
+    first: Player,
+    side: Player,
+}
+
+impl NumericalPosition {
+    pub fn new(first: Player) -> Self {
+        NumericalPosition {
+            cells: vec![None; CELL_COUNT],
+            first,
+            side: first,
+        }
+    }
+
+    pub fn cell(&self, id: usize) -> Option<u8> {
+        self.cells[id]
+    }
+
+    /// Numbers `player` has not placed yet.
+    pub fn numbers_left(&self, player: Player) -> Vec<u8> {
+        let parity = if player == self.first { 1 } else { 0 };
+        (1..=9)
+            .filter(|n| n % 2 == parity && !self.cells.contains(&Some(*n)))
+            .collect()
+    }
+
+    /// A move that wins on the spot for the side to move.
+    fn winning_move(&self) -> Option<usize> {
+        self.legal_moves().into_iter().find(|&mv| {
+            let mut position = self.clone();
+            position.play(mv);
+            position.state().winner() == Some(self.side)
+        })
+    }
+}
+
+impl Position for NumericalPosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.state() != GameState::InProgress {
+            return Vec::new();
+        }
+        let numbers = self.numbers_left(self.side);
+        (0..CELL_COUNT)
+            .filter(|&id| self.cells[id].is_none())
+            .flat_map(|id| numbers.iter().map(move |&n| encode_move(id, n)))
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        let (id, number) = decode_move(mv);
+        self.cells[id] = Some(number);
+        self.side = self.side.opponent();
+    }
+
+    fn state(&self) -> GameState {
+        let line = Topology::Flat.find_line(|line| {
+            line.iter()
+                .map(|&id| self.cells[id])
+                .sum::<Option<u8>>()
+                .is_some_and(|sum| sum == TARGET)
+        });
+        if let Some(line) = line {
+            return GameState::GameWon {
+                player: self.side.opponent(),
+                cells: line.to_vec(),
+            };
+        }
+        if self.cells.contains(&None) {
+            GameState::InProgress
+        } else {
+            GameState::Tie
+        }
+    }
+}
+
+/// Wins at once if possible, otherwise runs MCTS.
+pub fn best_move(position: &NumericalPosition, rng: &mut Rng) -> Option<usize> {
+    position
+        .winning_move()
+        .or_else(|| mcts::search(position, Budget::Iterations(NUMERICAL_AI_ITERATIONS), rng))
+}
+
+/// Numerical tic-tac-toe against the computer; the human plays `Player2`,
+/// moves first and so places the odd numbers.
+pub struct NumericalGame {
+    position: NumericalPosition,
+    selected: Option<(usize, usize)>,
+    number: Option<u8>,
+    rng: Rng,
+}
+
+impl NumericalGame {
+    pub fn new() -> Self {
+        NumericalGame {
+            position: NumericalPosition::new(Player::Player2),
+            selected: None,
+            number: Some(1),
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.position = NumericalPosition::new(Player::Player2);
+        self.selected = None;
+        self.number = Some(1);
+    }
+
+    pub(crate) fn position(&self) -> &NumericalPosition {
+        &self.position
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, x: usize, y: usize) {
+        let last = PLAY_FIELD_SIZE - 1;
+        self.selected = Some((x.min(last), y.min(last)));
+    }
+
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected.unwrap_or((0, 0));
+        let last = PLAY_FIELD_SIZE as isize - 1;
+        let x = (x as isize + dx).clamp(0, last) as usize;
+        let y = (y as isize + dy).clamp(0, last) as usize;
+        self.selected = Some((x, y));
+    }
+
+    /// Number the human will place next.
+    pub(crate) fn number(&self) -> Option<u8> {
+        self.number
+    }
+
+    pub(crate) fn pick_number(&mut self, number: u8) {
+        if self
+            .position
+            .numbers_left(Player::Player2)
+            .contains(&number)
+        {
+            self.number = Some(number);
+        }
+    }
+
+    /// Places the picked number for the human and lets the computer answer.
+    pub(crate) fn play_at(&mut self, x: usize, y: usize) -> bool {
+        let number = match self.number {
+            Some(number) => number,
+            None => return false,
+        };
+        let mv = encode_move(x + y * PLAY_FIELD_SIZE, number);
+        if self.position.side_to_move() != Player::Player2
+            || !self.position.legal_moves().contains(&mv)
+        {
+            return false;
+        }
+        self.position.play(mv);
+        if let Some(reply) = best_move(&self.position, &mut self.rng) {
+            self.position.play(reply);
+        }
+        self.number = self.position.numbers_left(Player::Player2).first().copied();
+        true
+    }
+}
+
+impl Default for NumericalGame {
+    fn default() -> Self {
+        NumericalGame::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_line_sums_to_fifteen() {
+        let mut position = NumericalPosition::new(Player::Player1);
+        for &mv in &[encode_move(0, 9), encode_move(1, 2), encode_move(8, 1)] {
+            position.play(mv);
+        }
+        assert_eq!(position.numbers_left(Player::Player1), vec![3, 5, 7]);
+        assert_eq!(position.numbers_left(Player::Player2), vec![4, 6, 8]);
+        position.play(encode_move(2, 4));
+        assert_eq!(position.state().winner(), Some(Player::Player2));
+    }
+
+    #[test]
+    fn takes_immediate_win() {
+        let mut position = NumericalPosition::new(Player::Player1);
+        for &mv in &[encode_move(0, 1), encode_move(1, 6), encode_move(4, 5)] {
+            position.play(mv);
+        }
+        // Player2 can finish the top row with 8 or the middle column with 4.
+        let mv = best_move(&position, &mut Rng::new(3)).expect("game in progress");
+        position.play(mv);
+        assert_eq!(position.state().winner(), Some(Player::Player2));
+    }
+}
@@ -0,0 +1,178 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{
+    NUMERICAL_PICKER_POS, NUMERICAL_PICKER_SIZE, PLAY_FIELD_POS, PLAY_FIELD_SIZE, SQUARE_SIZE,
+};
+use super::game::{FieldType, Game, GameState, Player, Topology};
+use super::game_ui::{finished_to_str, status_to_str};
+use super::numerical::NumericalGame;
+use super::position::Position;
+
+/// The human moves first, so the picker offers the odd numbers.
+const PICKER_NUMBERS: [u8; 5] = [1, 3, 5, 7, 9];
+
+/// Number under the mouse in the picker, if any.
+fn get_picker_number(x: f32, y: f32) -> Option<u8> {
+    let column = (x - NUMERICAL_PICKER_POS.0) / NUMERICAL_PICKER_SIZE;
+    let row = (y - NUMERICAL_PICKER_POS.1) / NUMERICAL_PICKER_SIZE;
+    if (0.0..1.0).contains(&column) && (0.0..PICKER_NUMBERS.len() as f32).contains(&row) {
+        Some(PICKER_NUMBERS[row as usize])
+    } else {
+        None
+    }
+}
+
+fn number_key(keycode: KeyCode) -> Option<u8> {
+    let number = match keycode {
+        KeyCode::Key1 | KeyCode::Numpad1 => 1,
+        KeyCode::Key3 | KeyCode::Numpad3 => 3,
+        KeyCode::Key5 | KeyCode::Numpad5 => 5,
+        KeyCode::Key7 | KeyCode::Numpad7 => 7,
+        KeyCode::Key9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    };
+    Some(number)
+}
+
+impl Mode for NumericalGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let Some(number) = get_picker_number(x, y) {
+            self.pick_number(number);
+        } else if Game::get_field_type(x, y) == FieldType::PlayField {
+            let (cell_x, cell_y) = Game::get_cell(x, y);
+            self.play_at(cell_x, cell_y);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if Game::get_field_type(x, y) == FieldType::PlayField {
+            let (cell_x, cell_y) = Game::get_cell(x, y);
+            self.select(cell_x, cell_y);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::Left => self.move_selected(-1, 0),
+            KeyCode::Right => self.move_selected(1, 0),
+            KeyCode::Up => self.move_selected(0, -1),
+            KeyCode::Down => self.move_selected(0, 1),
+            KeyCode::Space => {
+                if let Some((x, y)) = self.selected() {
+                    self.play_at(x, y);
+                }
+            }
+            _ => {
+                if let Some(number) = number_key(keycode) {
+                    self.pick_number(number);
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+        let cursor_color = rgb(painter.theme().cursor);
+        let used_color = rgb(painter.theme().faded);
+        let text_color = rgb(painter.theme().text);
+
+        painter.draw_field(mb, &[]);
+        for id in 0..PLAY_FIELD_SIZE * PLAY_FIELD_SIZE {
+            if let Some(number) = position.cell(id) {
+                let (x, y) = (id % PLAY_FIELD_SIZE, id / PLAY_FIELD_SIZE);
+                draw_number(ctx, PLAY_FIELD_POS, SQUARE_SIZE, number, x, y, text_color);
+            }
+        }
+
+        let numbers_left = position.numbers_left(Player::Player2);
+        let picker_size = (1, PICKER_NUMBERS.len());
+        painter.draw_board_grid(
+            mb,
+            NUMERICAL_PICKER_POS,
+            NUMERICAL_PICKER_SIZE,
+            picker_size,
+            2.0,
+        );
+        for (row, number) in PICKER_NUMBERS.iter().copied().enumerate() {
+            let color = if numbers_left.contains(&number) {
+                text_color
+            } else {
+                used_color
+            };
+            draw_number(
+                ctx,
+                NUMERICAL_PICKER_POS,
+                NUMERICAL_PICKER_SIZE,
+                number,
+                0,
+                row,
+                color,
+            );
+            if self.number() == Some(number) {
+                draw_frame(
+                    mb,
+                    NUMERICAL_PICKER_POS,
+                    NUMERICAL_PICKER_SIZE,
+                    0,
+                    row,
+                    cursor_color,
+                );
+            }
+        }
+
+        let game_state = position.state();
+        match &game_state {
+            GameState::GameWon { cells, .. } => {
+                painter.draw_red_line(mb, cells, Topology::Flat, 1.0)
+            }
+            GameState::InProgress => {
+                if let Some((x, y)) = self.selected() {
+                    painter.draw_selected_cell(mb, x, y);
+                }
+            }
+            _ => (),
+        }
+
+        let text = status_to_str(
+            &game_state,
+            "Numerical, pick an odd number and make a line sum to 15",
+        );
+        painter.draw_text(ctx, &text);
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        finished_to_str(&self.position().state())
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
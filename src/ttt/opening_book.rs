@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::sync::OnceLock;
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Game, GameState, Player};
+use super::rng::Rng;
+use super::tablebase::{self, position_key};
+
+static OPENING_BOOK: OnceLock<OpeningBook> = OnceLock::new();
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BookMove {
+    pub cell: usize,
+    pub weight: u32,
+}
+
+/// Candidate moves for early positions, keyed by `tablebase::position_key`.
+///
+/// On disk the book is plain text, one position per line:
+/// `<key> <cell>:<weight> <cell>:<weight> ...`, with `#` starting a comment.
+#[derive(Debug, Default, PartialEq)]
+pub struct OpeningBook {
+    entries: BTreeMap<usize, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    /// Every optimal move from the tablebase for the first `plies` plies,
+    /// all with the same weight.
+    pub fn from_solver(plies: usize) -> Self {
+        let mut book = OpeningBook::default();
+        let mut cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+        for &side in &[Player::Player1, Player::Player2] {
+            book.add_solver_moves(&mut cells, side, plies);
+        }
+        book
+    }
+
+    fn add_solver_moves(&mut self, cells: &mut [Cell], side: Player, plies: usize) {
+        let key = position_key(cells, side);
+        if plies == 0
+            || self.entries.contains_key(&key)
+            || Game::get_game_state(cells) != GameState::InProgress
+        {
+            return;
+        }
+
+        let best_moves = tablebase::global().best_moves(cells, side);
+        for &cell in &best_moves {
+            self.add(key, cell, 1);
+        }
+        for cell in best_moves {
+            cells[cell] = Cell::Player(side);
+            self.add_solver_moves(cells, side.opponent(), plies - 1);
+            cells[cell] = Cell::Empty;
+        }
+    }
+
+    /// Plays `games` perfect games picking randomly between optimal moves and
+    /// weights each book move by how often it was played.
+    pub fn from_self_play(games: usize, plies: usize, rng: &mut Rng) -> Self {
+        let mut book = OpeningBook::default();
+        for game in 0..games {
+            let mut cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+            let mut side = if game % 2 == 0 {
+                Player::Player1
+            } else {
+                Player::Player2
+            };
+            for _ in 0..plies {
+                if Game::get_game_state(&cells) != GameState::InProgress {
+                    break;
+                }
+                let best_moves = tablebase::global().best_moves(&cells, side);
+                let cell = best_moves[rng.below(best_moves.len())];
+                book.add(position_key(&cells, side), cell, 1);
+                cells[cell] = Cell::Player(side);
+                side = side.opponent();
+            }
+        }
+        book
+    }
+
+    fn add(&mut self, key: usize, cell: usize, weight: u32) {
+        let moves = self.entries.entry(key).or_default();
+        match moves.iter_mut().find(|m| m.cell == cell) {
+            Some(book_move) => book_move.weight += weight,
+            None => moves.push(BookMove { cell, weight }),
+        }
+    }
+
+    pub fn moves(&self, cells: &[Cell], side: Player) -> &[BookMove] {
+        self.entries
+            .get(&position_key(cells, side))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Book moves for `side` that can still be played.
+    fn playable(&self, cells: &[Cell], side: Player) -> Vec<BookMove> {
+        self.moves(cells, side)
+            .iter()
+            .copied()
+            .filter(|m| cells.get(m.cell) == Some(&Cell::Empty) && m.weight > 0)
+            .collect()
+    }
+
+    /// The heaviest book move for `side`, the lowest cell among equals.
+    pub fn heaviest(&self, cells: &[Cell], side: Player) -> Option<usize> {
+        self.playable(cells, side)
+            .iter()
+            .max_by_key(|m| (m.weight, std::cmp::Reverse(m.cell)))
+            .map(|m| m.cell)
+    }
+
+    /// Chooses a book move for `side`. A `variety` of 0 always plays the
+    /// heaviest move, 1 samples in proportion to the weights and values in
+    /// between sharpen the weights towards the heaviest move.
+    pub fn pick(&self, cells: &[Cell], side: Player, variety: f32, rng: &mut Rng) -> Option<usize> {
+        if variety <= 0.0 {
+            return self.heaviest(cells, side);
+        }
+        let moves = self.playable(cells, side);
+        if moves.is_empty() {
+            return None;
+        }
+
+        let exponent = 1.0 / f64::from(variety.min(1.0));
+        let weights: Vec<f64> = moves
+            .iter()
+            .map(|m| f64::from(m.weight).powf(exponent))
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let mut target = rng.next_u64() as f64 / u64::MAX as f64 * total;
+        for (book_move, weight) in moves.iter().zip(&weights) {
+            if target < *weight {
+                return Some(book_move.cell);
+            }
+            target -= weight;
+        }
+        moves.last().map(|m| m.cell)
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "# tictactoe opening book: <key> <cell>:<weight> ..."
+        )?;
+        for (key, moves) in &self.entries {
+            write!(writer, "{}", key)?;
+            for book_move in moves {
+                write!(writer, " {}:{}", book_move.cell, book_move.weight)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut book = OpeningBook::default();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let key = parse_field(fields.next(), line)?;
+            for field in fields {
+                let mut parts = field.splitn(2, ':');
+                let cell = parse_field(parts.next(), line)?;
+                let weight = parse_field(parts.next(), line)?;
+                book.add(key, cell, weight);
+            }
+        }
+        Ok(book)
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(field: Option<&str>, line: &str) -> io::Result<T> {
+    field.and_then(|f| f.parse().ok()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("bad opening book line '{}'", line),
+        )
+    })
+}
+
+/// Shared opening book, generated from the solver unless one was installed.
+pub fn global() -> &'static OpeningBook {
+    OPENING_BOOK.get_or_init(|| OpeningBook::from_solver(2))
+}
+
+pub fn install(book: OpeningBook) {
+    let _ = OPENING_BOOK.set(book);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_round_trip() {
+        let book = OpeningBook::from_self_play(50, 3, &mut Rng::new(1));
+        let mut bytes = Vec::new();
+        book.write_to(&mut bytes).unwrap();
+        assert_eq!(OpeningBook::read_from(bytes.as_slice()).unwrap(), book);
+    }
+
+    #[test]
+    fn variety_controls_choice() {
+        let cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+        let book = OpeningBook::read_from("0 4:1 0:3 8:3".as_bytes()).unwrap();
+        let mut rng = Rng::new(3);
+        assert_eq!(book.pick(&cells, Player::Player1, 0.0, &mut rng), Some(0));
+
+        let mut seen = Vec::new();
+        for _ in 0..100 {
+            let cell = book.pick(&cells, Player::Player1, 1.0, &mut rng).unwrap();
+            if !seen.contains(&cell) {
+                seen.push(cell);
+            }
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, vec![0, 4, 8]);
+    }
+}
@@ -0,0 +1,41 @@
+use super::game::{Cell, Game, GameState, Player};
+
+/// A game position as seen by the generic searches: moves are plain indices
+/// whose meaning is up to the rule set.
+pub trait Position: Clone {
+    fn side_to_move(&self) -> Player;
+
+    fn legal_moves(&self) -> Vec<usize>;
+
+    fn play(&mut self, mv: usize);
+
+    fn state(&self) -> GameState;
+}
+
+/// The classic 3x3 board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Classic {
+    pub cells: Vec<Cell>,
+    pub side: Player,
+}
+
+impl Position for Classic {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        (0..self.cells.len())
+            .filter(|&i| self.cells[i] == Cell::Empty)
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        self.cells[mv] = Cell::Player(self.side);
+        self.side = self.side.opponent();
+    }
+
+    fn state(&self) -> GameState {
+        Game::get_game_state(&self.cells)
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Game, GameState, Player, RuleSet, Topology};
+use super::game_logic::{self, TieBreak};
+use super::rng::Rng;
+use super::tablebase::position_key;
+
+const CELL_COUNT: usize = PLAY_FIELD_SIZE * PLAY_FIELD_SIZE;
+
+/// Puzzles used when no puzzle file is given, three each of win in 1, 2 and 3.
+const BUILTIN: &str = "\
+XO..XO... 1
+.XOXOOX.. 1
+OXX.O.O.X 1
+XXOO..... 2
+X..OX...O 2
+.XOX.OO.X 2
+...O...X. 3
+..X.....O 3
+.OO..X.X. 3
+";
+
+/// Position where X, the human, is to move and can force a win within
+/// `moves` of their own moves but no sooner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    cells: Vec<Cell>,
+    moves: usize,
+}
+
+impl Puzzle {
+    pub fn new(cells: Vec<Cell>, moves: usize) -> Self {
+        Puzzle { cells, moves }
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Checks with the minimax solver that the win takes exactly `moves`.
+    pub fn is_valid(&self) -> bool {
+        shortest_win(&self.cells, self.moves) == Some(self.moves)
+    }
+
+    /// Cells as `.`, `O` and `X` row by row, then the number of moves.
+    pub fn to_line(&self) -> String {
+        let cells: String = self
+            .cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Player(Player::Player1) => 'O',
+                Cell::Player(Player::Player2) => 'X',
+                Cell::Empty | Cell::Blocked => '.',
+            })
+            .collect();
+        format!("{} {}", cells, self.moves)
+    }
+
+    pub fn from_line(line: &str) -> io::Result<Self> {
+        let bad_puzzle =
+            || io::Error::new(io::ErrorKind::InvalidData, format!("bad puzzle '{}'", line));
+        let (cells, moves) = line.trim().split_once(' ').ok_or_else(bad_puzzle)?;
+        let cells = cells
+            .chars()
+            .map(|c| match c {
+                '.' => Some(Cell::Empty),
+                'O' => Some(Cell::Player(Player::Player1)),
+                'X' => Some(Cell::Player(Player::Player2)),
+                _ => None,
+            })
+            .collect::<Option<Vec<Cell>>>()
+            .filter(|cells| cells.len() == CELL_COUNT)
+            .ok_or_else(bad_puzzle)?;
+        let moves = moves.trim().parse().map_err(|_| bad_puzzle())?;
+        Ok(Puzzle { cells, moves })
+    }
+}
+
+/// Fewest moves, up to `max`, in which X forces a win.
+fn shortest_win(cells: &[Cell], max: usize) -> Option<usize> {
+    if Game::get_game_state(cells) != GameState::InProgress {
+        return None;
+    }
+    let mut cells = cells.to_vec();
+    (1..=max)
+        .find(|&moves| !game_logic::winning_moves(&mut cells, Player::Player2, moves).is_empty())
+}
+
+/// A puzzle worth asking: X wins in exactly `moves`, and not with every move.
+fn mine(cells: &[Cell], moves: usize) -> Option<Puzzle> {
+    if shortest_win(cells, moves) != Some(moves) {
+        return None;
+    }
+    let legal = cells.iter().filter(|&&cell| cell == Cell::Empty).count();
+    let winning = game_logic::winning_moves(&mut cells.to_vec(), Player::Player2, moves);
+    if winning.len() < legal {
+        Some(Puzzle::new(cells.to_vec(), moves))
+    } else {
+        None
+    }
+}
+
+/// Reads puzzle lines, rejecting any that can't be solved in exactly the
+/// moves they give.
+pub fn read_puzzles<R: BufRead>(reader: R) -> io::Result<Vec<Puzzle>> {
+    let mut puzzles = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let puzzle = Puzzle::from_line(line)?;
+        if !puzzle.is_valid() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("puzzle '{}' isn't a forced win in exactly its moves", line),
+            ));
+        }
+        puzzles.push(puzzle);
+    }
+    Ok(puzzles)
+}
+
+pub fn write_puzzles<W: Write>(writer: &mut W, puzzles: &[Puzzle]) -> io::Result<()> {
+    writeln!(writer, "# tictactoe puzzles: <cells> <moves>, X to move")?;
+    for puzzle in puzzles {
+        writeln!(writer, "{}", puzzle.to_line())?;
+    }
+    Ok(())
+}
+
+pub fn builtin() -> Vec<Puzzle> {
+    read_puzzles(BUILTIN.as_bytes()).expect("built-in puzzles parse")
+}
+
+/// Every position of a game X opened that makes a win in `moves` puzzle.
+pub fn mine_from_solver(moves: usize) -> Vec<Puzzle> {
+    fn visit(
+        cells: &mut [Cell],
+        side: Player,
+        moves: usize,
+        seen: &mut HashSet<usize>,
+        puzzles: &mut Vec<Puzzle>,
+    ) {
+        if !seen.insert(position_key(cells, side))
+            || Game::get_game_state(cells) != GameState::InProgress
+        {
+            return;
+        }
+        if side == Player::Player2 {
+            puzzles.extend(mine(cells, moves));
+        }
+        for i in 0..cells.len() {
+            if cells[i] == Cell::Empty {
+                cells[i] = Cell::Player(side);
+                visit(cells, side.opponent(), moves, seen, puzzles);
+                cells[i] = Cell::Empty;
+            }
+        }
+    }
+
+    let mut puzzles = Vec::new();
+    let mut cells = vec![Cell::Empty; CELL_COUNT];
+    visit(
+        &mut cells,
+        Player::Player2,
+        moves,
+        &mut HashSet::new(),
+        &mut puzzles,
+    );
+    puzzles
+}
+
+/// Puzzles met along the way in `games` games of random moves, X first.
+pub fn mine_from_self_play(games: usize, moves: usize, rng: &mut Rng) -> Vec<Puzzle> {
+    let mut seen = HashSet::new();
+    let mut puzzles = Vec::new();
+    for _ in 0..games {
+        let mut cells = vec![Cell::Empty; CELL_COUNT];
+        let mut side = Player::Player2;
+        while Game::get_game_state(&cells) == GameState::InProgress {
+            if side == Player::Player2 && seen.insert(position_key(&cells, side)) {
+                puzzles.extend(mine(&cells, moves));
+            }
+            let empty: Vec<usize> = (0..CELL_COUNT)
+                .filter(|&i| cells[i] == Cell::Empty)
+                .collect();
+            cells[empty[rng.below(empty.len())]] = Cell::Player(side);
+            side = side.opponent();
+        }
+    }
+    puzzles
+}
+
+/// Puzzles the player has solved, kept in a file of puzzle lines if given.
+#[derive(Default)]
+pub struct SolvedPuzzles {
+    path: Option<PathBuf>,
+    solved: BTreeSet<String>,
+}
+
+impl SolvedPuzzles {
+    /// Opens `path`, starting with nothing solved if it doesn't exist yet.
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let mut solved = BTreeSet::new();
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    solved.insert(line?.trim().to_string());
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e),
+        }
+        Ok(SolvedPuzzles {
+            path: Some(path),
+            solved,
+        })
+    }
+
+    pub fn contains(&self, puzzle: &Puzzle) -> bool {
+        self.solved.contains(&puzzle.to_line())
+    }
+
+    pub fn insert(&mut self, puzzle: &Puzzle) -> io::Result<()> {
+        let line = puzzle.to_line();
+        if !self.solved.insert(line.clone()) {
+            return Ok(());
+        }
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PuzzleStatus {
+    Solving,
+    Solved,
+    /// The last move doesn't force a win in time.
+    Failed,
+}
+
+/// Works through a list of puzzles; the computer defends as long as it can.
+pub struct PuzzleGame {
+    puzzles: Vec<Puzzle>,
+    index: usize,
+    cells: Vec<Cell>,
+    moves_left: usize,
+    status: PuzzleStatus,
+    selected: Option<(usize, usize)>,
+    solved: SolvedPuzzles,
+    tie_break: TieBreak,
+}
+
+impl PuzzleGame {
+    /// `puzzles` must not be empty.
+    pub fn new(puzzles: Vec<Puzzle>, solved: SolvedPuzzles) -> Self {
+        let mut game = PuzzleGame {
+            puzzles,
+            index: 0,
+            cells: Vec::new(),
+            moves_left: 0,
+            status: PuzzleStatus::Solving,
+            selected: None,
+            solved,
+            tie_break: TieBreak::First,
+        };
+        game.clear();
+        game
+    }
+
+    /// Starts the current puzzle over.
+    pub(crate) fn clear(&mut self) {
+        let puzzle = &self.puzzles[self.index];
+        self.cells = puzzle.cells.clone();
+        self.moves_left = puzzle.moves;
+        self.status = PuzzleStatus::Solving;
+        self.selected = None;
+    }
+
+    /// Moves `delta` puzzles on, wrapping around at either end.
+    pub(crate) fn next_puzzle(&mut self, delta: isize) {
+        let count = self.puzzles.len() as isize;
+        self.index = (self.index as isize + delta).rem_euclid(count) as usize;
+        self.clear();
+    }
+
+    pub(crate) fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub(crate) fn status(&self) -> PuzzleStatus {
+        self.status
+    }
+
+    pub(crate) fn moves_left(&self) -> usize {
+        self.moves_left
+    }
+
+    /// Number of the current puzzle from 1, the count and whether it was
+    /// solved before.
+    pub(crate) fn progress(&self) -> (usize, usize, bool) {
+        let solved = self.solved.contains(&self.puzzles[self.index]);
+        (self.index + 1, self.puzzles.len(), solved)
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, x: usize, y: usize) {
+        let last = PLAY_FIELD_SIZE - 1;
+        self.selected = Some((x.min(last), y.min(last)));
+    }
+
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected.unwrap_or((0, 0));
+        let last = PLAY_FIELD_SIZE as isize - 1;
+        let x = (x as isize + dx).clamp(0, last) as usize;
+        let y = (y as isize + dy).clamp(0, last) as usize;
+        self.selected = Some((x, y));
+    }
+
+    /// Plays the human's answer. A move that doesn't keep a forced win within
+    /// the moves left fails the puzzle; otherwise the computer defends.
+    pub(crate) fn play_at(&mut self, x: usize, y: usize) -> bool {
+        let id = x + y * PLAY_FIELD_SIZE;
+        if self.status != PuzzleStatus::Solving || self.cells[id] != Cell::Empty {
+            return false;
+        }
+        let winning = game_logic::winning_moves(&mut self.cells, Player::Player2, self.moves_left);
+        self.cells[id] = Cell::Player(Player::Player2);
+        if !winning.contains(&id) {
+            self.status = PuzzleStatus::Failed;
+            return true;
+        }
+
+        self.moves_left -= 1;
+        if Game::get_game_state(&self.cells).winner() == Some(Player::Player2) {
+            self.status = PuzzleStatus::Solved;
+            if let Err(e) = self.solved.insert(&self.puzzles[self.index]) {
+                eprintln!("Can't save solved puzzles: {}", e);
+            }
+            return true;
+        }
+        let lines = Topology::Flat.lines();
+        let reply = game_logic::get_best_move(
+            &mut self.cells,
+            RuleSet::Standard,
+            lines,
+            &mut self.tie_break,
+        );
+        if let Some(reply) = reply {
+            self.cells[reply] = Cell::Player(Player::Player1);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_puzzles_are_valid() {
+        let puzzles = builtin();
+        assert_eq!(puzzles.len(), 9);
+        for puzzle in &puzzles {
+            assert!(puzzle.is_valid(), "{}", puzzle.to_line());
+            assert_eq!(&Puzzle::from_line(&puzzle.to_line()).unwrap(), puzzle);
+        }
+        assert!(read_puzzles("XO..XO... 0".as_bytes()).is_err());
+        assert!(read_puzzles("XO..XO... 2".as_bytes()).is_err());
+        assert!(read_puzzles("XXX.OO... 1".as_bytes()).is_err());
+        assert!(mine_from_self_play(20, 2, &mut Rng::new(5))
+            .iter()
+            .all(Puzzle::is_valid));
+    }
+
+    #[test]
+    fn solving_and_failing() {
+        // Win in 2 from "XXOO.....": only the center keeps two threats.
+        let mut game = PuzzleGame::new(builtin(), SolvedPuzzles::default());
+        game.next_puzzle(3);
+        game.play_at(2, 2);
+        assert_eq!(game.status(), PuzzleStatus::Failed);
+
+        game.clear();
+        game.play_at(1, 1);
+        assert_eq!(game.status(), PuzzleStatus::Solving);
+        assert_eq!(game.moves_left(), 1);
+        let answer = game_logic::winning_moves(&mut game.cells.clone(), Player::Player2, 1)[0];
+        game.play_at(answer % PLAY_FIELD_SIZE, answer / PLAY_FIELD_SIZE);
+        assert_eq!(game.status(), PuzzleStatus::Solved);
+        assert!(game.progress().2);
+    }
+}
@@ -0,0 +1,108 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, FieldType, Game, GameState, Topology};
+use super::puzzle::{PuzzleGame, PuzzleStatus};
+
+impl Mode for PuzzleGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left && Game::get_field_type(x, y) == FieldType::PlayField {
+            let (cell_x, cell_y) = Game::get_cell(x, y);
+            self.play_at(cell_x, cell_y);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if Game::get_field_type(x, y) == FieldType::PlayField {
+            let (cell_x, cell_y) = Game::get_cell(x, y);
+            self.select(cell_x, cell_y);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::N | KeyCode::PageDown => self.next_puzzle(1),
+            KeyCode::P | KeyCode::PageUp => self.next_puzzle(-1),
+            KeyCode::Left => self.move_selected(-1, 0),
+            KeyCode::Right => self.move_selected(1, 0),
+            KeyCode::Up => self.move_selected(0, -1),
+            KeyCode::Down => self.move_selected(0, 1),
+            KeyCode::Space => {
+                if let Some((x, y)) = self.selected() {
+                    self.play_at(x, y);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let cells = self.cells();
+
+        painter.draw_field(mb, &[]);
+        for (id, &cell) in cells.iter().enumerate() {
+            if let Cell::Player(player) = cell {
+                painter.draw_player(mb, player, id % PLAY_FIELD_SIZE, id / PLAY_FIELD_SIZE, 1.0);
+            }
+        }
+
+        match Game::get_game_state(cells) {
+            GameState::GameWon { cells, .. } => {
+                painter.draw_red_line(mb, &cells, Topology::Flat, 1.0)
+            }
+            GameState::InProgress if self.status() == PuzzleStatus::Solving => {
+                if let Some((x, y)) = self.selected() {
+                    painter.draw_selected_cell(mb, x, y);
+                }
+            }
+            _ => (),
+        }
+
+        let (number, count, solved_before) = self.progress();
+        let mut text = format!("Puzzle {}/{}", number, count);
+        if solved_before {
+            text += " (solved)";
+        }
+        text += &match self.status() {
+            PuzzleStatus::Solving => format!(": X to win in {}", self.moves_left()),
+            PuzzleStatus::Solved => String::from(": solved, N for the next one"),
+            PuzzleStatus::Failed => String::from(": no forced win, R to retry"),
+        };
+        painter.draw_text(ctx, &text);
+        painter.draw_sprites(ctx)?;
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    /// Puzzles go on with N and R rather than through the game-over screen.
+    fn result(&self) -> Option<String> {
+        None
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::config::QUBIC_AI_TIME_MS;
+use super::game::{Cell, GameState, Player};
+use super::mcts::{self, Budget};
+use super::position::Position;
+use super::rng::Rng;
+
+pub const QUBIC_SIZE: usize = 4;
+pub const QUBIC_CELLS: usize = QUBIC_SIZE * QUBIC_SIZE * QUBIC_SIZE;
+
+static LINES: OnceLock<Vec<[usize; QUBIC_SIZE]>> = OnceLock::new();
+
+/// Cell index of (`x`, `y`) on `layer`.
+pub fn cell_index(layer: usize, x: usize, y: usize) -> usize {
+    layer * QUBIC_SIZE * QUBIC_SIZE + y * QUBIC_SIZE + x
+}
+
+/// All 76 winning lines of the 4x4x4 cube: rows, columns and pillars,
+/// the diagonals of every plane and the four space diagonals.
+pub fn lines() -> &'static [[usize; QUBIC_SIZE]] {
+    LINES.get_or_init(|| {
+        let size = QUBIC_SIZE as isize;
+        let inside = |v: isize| (0..size).contains(&v);
+        let mut lines = Vec::new();
+        for layer in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    for dz in -1..=1 {
+                        for dy in -1..=1 {
+                            for dx in -1..=1 {
+                                // One of each pair of opposite directions.
+                                if (dz, dy, dx) <= (0, 0, 0) {
+                                    continue;
+                                }
+                                let end = (
+                                    layer + dz * (size - 1),
+                                    y + dy * (size - 1),
+                                    x + dx * (size - 1),
+                                );
+                                let before = (layer - dz, y - dy, x - dx);
+                                if !(inside(end.0) && inside(end.1) && inside(end.2))
+                                    || (inside(before.0) && inside(before.1) && inside(before.2))
+                                {
+                                    continue;
+                                }
+                                let mut line = [0; QUBIC_SIZE];
+                                for (i, cell) in line.iter_mut().enumerate() {
+                                    let i = i as isize;
+                                    *cell = cell_index(
+                                        (layer + dz * i) as usize,
+                                        (x + dx * i) as usize,
+                                        (y + dy * i) as usize,
+                                    );
+                                }
+                                lines.push(line);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        lines
+    })
+}
+
+/// 4x4x4 board where four in a row along any of the 76 lines wins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QubicPosition {
+    cells: Vec<Cell>,
+    side: Player,
+}
+
+impl QubicPosition {
+    pub fn new(first: Player) -> Self {
+        QubicPosition {
+            cells: vec![Cell::Empty; QUBIC_CELLS],
+            side: first,
+        }
+    }
+
+    pub fn cell(&self, id: usize) -> Cell {
+        self.cells[id]
+    }
+
+    /// A cell that completes a line of `player`, if any.
+    fn winning_cell(&self, player: Player) -> Option<usize> {
+        lines().iter().find_map(|line| {
+            let ours = line
+                .iter()
+                .filter(|&&id| self.cells[id] == Cell::Player(player))
+                .count();
+            let empty: Vec<usize> = line
+                .iter()
+                .copied()
+                .filter(|&id| self.cells[id] == Cell::Empty)
+                .collect();
+            if ours == QUBIC_SIZE - 1 && empty.len() == 1 {
+                Some(empty[0])
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Position for QubicPosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        (0..QUBIC_CELLS)
+            .filter(|&i| self.cells[i] == Cell::Empty)
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        self.cells[mv] = Cell::Player(self.side);
+        self.side = self.side.opponent();
+    }
+
+    fn state(&self) -> GameState {
+        for line in lines() {
+            if let Cell::Player(player) = self.cells[line[0]] {
+                if line.iter().all(|&id| self.cells[id] == self.cells[line[0]]) {
+                    return GameState::GameWon {
+                        player,
+                        cells: line.to_vec(),
+                    };
+                }
+            }
+        }
+        if self.cells.contains(&Cell::Empty) {
+            GameState::InProgress
+        } else {
+            GameState::Tie
+        }
+    }
+}
+
+/// Wins or blocks an immediate line, otherwise runs MCTS for `budget`.
+pub fn best_move(position: &QubicPosition, budget: Duration, rng: &mut Rng) -> Option<usize> {
+    if position.state() != GameState::InProgress {
+        return None;
+    }
+    let side = position.side_to_move();
+    position
+        .winning_cell(side)
+        .or_else(|| position.winning_cell(side.opponent()))
+        .or_else(|| mcts::search(position, Budget::Time(budget), rng))
+}
+
+/// Qubic against the computer; the human plays `Player2` and moves first.
+pub struct QubicGame {
+    position: QubicPosition,
+    /// Cursor as (layer, x, y).
+    selected: Option<(usize, usize, usize)>,
+    rng: Rng,
+}
+
+impl QubicGame {
+    pub fn new() -> Self {
+        QubicGame {
+            position: QubicPosition::new(Player::Player2),
+            selected: None,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.position = QubicPosition::new(Player::Player2);
+        self.selected = None;
+    }
+
+    pub(crate) fn position(&self) -> &QubicPosition {
+        &self.position
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, layer: usize, x: usize, y: usize) {
+        self.selected = Some((layer, x, y));
+    }
+
+    /// Left and right run through the layers as if they were one long row.
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize, dlayer: isize) {
+        let (layer, x, y) = self.selected.unwrap_or((0, 0, 0));
+        let size = QUBIC_SIZE as isize;
+        let row = (layer as isize * size + x as isize + dx).clamp(0, size * size - 1);
+        let layer = (row / size + dlayer).clamp(0, size - 1);
+        let y = (y as isize + dy).clamp(0, size - 1);
+        self.selected = Some((layer as usize, (row % size) as usize, y as usize));
+    }
+
+    pub(crate) fn play_at(&mut self, layer: usize, x: usize, y: usize) -> bool {
+        let mv = cell_index(layer, x, y);
+        if self.position.side_to_move() != Player::Player2
+            || self.position.state() != GameState::InProgress
+            || self.position.cell(mv) != Cell::Empty
+        {
+            return false;
+        }
+        self.position.play(mv);
+        let budget = Duration::from_millis(QUBIC_AI_TIME_MS);
+        if let Some(reply) = best_move(&self.position, budget, &mut self.rng) {
+            self.position.play(reply);
+        }
+        true
+    }
+}
+
+impl Default for QubicGame {
+    fn default() -> Self {
+        QubicGame::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_76_lines() {
+        assert_eq!(lines().len(), 76);
+    }
+
+    #[test]
+    fn space_diagonal_wins() {
+        let mut position = QubicPosition::new(Player::Player1);
+        for i in 0..QUBIC_SIZE {
+            position.cells[cell_index(i, i, i)] = Cell::Player(Player::Player1);
+        }
+        assert_eq!(
+            position.state(),
+            GameState::GameWon {
+                player: Player::Player1,
+                cells: vec![0, 21, 42, 63]
+            }
+        );
+    }
+
+    #[test]
+    fn blocks_open_line() {
+        let mut position = QubicPosition::new(Player::Player1);
+        for x in 0..3 {
+            position.cells[cell_index(2, x, 1)] = Cell::Player(Player::Player2);
+        }
+        let mv = best_move(&position, Duration::from_millis(10), &mut Rng::new(1));
+        assert_eq!(mv, Some(cell_index(2, 3, 1)));
+    }
+}
@@ -0,0 +1,136 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{QUBIC_FIELD_POS, QUBIC_LAYER_GAP, QUBIC_SQUARE_SIZE};
+use super::game::{Cell, GameState};
+use super::game_ui::{finished_to_str, status_to_str};
+use super::position::Position;
+use super::qubic::{cell_index, QubicGame, QUBIC_SIZE};
+
+const LAYER_SIZE: f32 = QUBIC_SQUARE_SIZE * QUBIC_SIZE as f32;
+
+fn layer_pos(layer: usize) -> (f32, f32) {
+    (
+        QUBIC_FIELD_POS.0 + (LAYER_SIZE + QUBIC_LAYER_GAP) * layer as f32,
+        QUBIC_FIELD_POS.1,
+    )
+}
+
+/// Layer and cell under the mouse, if any.
+fn get_cell(x: f32, y: f32) -> Option<(usize, usize, usize)> {
+    (0..QUBIC_SIZE).find_map(|layer| {
+        let pos = layer_pos(layer);
+        let cell_x = (x - pos.0) / QUBIC_SQUARE_SIZE;
+        let cell_y = (y - pos.1) / QUBIC_SQUARE_SIZE;
+        let size = QUBIC_SIZE as f32;
+        if (0.0..size).contains(&cell_x) && (0.0..size).contains(&cell_y) {
+            Some((layer, cell_x as usize, cell_y as usize))
+        } else {
+            None
+        }
+    })
+}
+
+impl Mode for QubicGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left {
+            if let Some((layer, cell_x, cell_y)) = get_cell(x, y) {
+                self.play_at(layer, cell_x, cell_y);
+            }
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some((layer, cell_x, cell_y)) = get_cell(x, y) {
+            self.select(layer, cell_x, cell_y);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::Left => self.move_selected(-1, 0, 0),
+            KeyCode::Right => self.move_selected(1, 0, 0),
+            KeyCode::Up => self.move_selected(0, -1, 0),
+            KeyCode::Down => self.move_selected(0, 1, 0),
+            KeyCode::PageUp => self.move_selected(0, 0, -1),
+            KeyCode::PageDown => self.move_selected(0, 0, 1),
+            KeyCode::Space => {
+                if let Some((layer, x, y)) = self.selected() {
+                    self.play_at(layer, x, y);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+        let cursor_color = rgb(painter.theme().cursor);
+
+        for layer in 0..QUBIC_SIZE {
+            let pos = layer_pos(layer);
+            painter.draw_grid(mb, pos, QUBIC_SQUARE_SIZE, QUBIC_SIZE, 3.0);
+            for y in 0..QUBIC_SIZE {
+                for x in 0..QUBIC_SIZE {
+                    if let Cell::Player(player) = position.cell(cell_index(layer, x, y)) {
+                        painter.draw_player_at(mb, pos, QUBIC_SQUARE_SIZE, player, x, y);
+                    }
+                }
+            }
+        }
+
+        let game_state = position.state();
+        match &game_state {
+            // A winning line can run through every layer, so mark its cells.
+            GameState::GameWon { cells, .. } => {
+                for &id in cells {
+                    let layer = id / (QUBIC_SIZE * QUBIC_SIZE);
+                    let (x, y) = (id % QUBIC_SIZE, id / QUBIC_SIZE % QUBIC_SIZE);
+                    draw_frame(mb, layer_pos(layer), QUBIC_SQUARE_SIZE, x, y, cursor_color);
+                }
+            }
+            GameState::InProgress => {
+                if let Some((layer, x, y)) = self.selected() {
+                    draw_frame(mb, layer_pos(layer), QUBIC_SQUARE_SIZE, x, y, cursor_color);
+                }
+            }
+            _ => (),
+        }
+
+        let text = status_to_str(&game_state, "Qubic, PageUp/PageDown switch layers");
+        painter.draw_text(ctx, &text);
+        painter.draw_sprites(ctx)?;
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        finished_to_str(&self.position().state())
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
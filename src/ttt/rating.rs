@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use super::game::Player;
+use super::record::{self, GameRecord};
+
+const INITIAL_RATING: f64 = 1500.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub elo: f64,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Rating {
+            elo: INITIAL_RATING,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        }
+    }
+}
+
+impl Rating {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+}
+
+/// Elo ratings of every human and engine that has played a rated game.
+///
+/// Stored as text, one player per line:
+/// `<name>\t<elo>\t<wins>\t<draws>\t<losses>`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Ratings {
+    players: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    /// Replays `records` in order, so ratings can always be rebuilt from the
+    /// stored games.
+    pub fn from_records(records: &[GameRecord]) -> Self {
+        let mut ratings = Ratings::default();
+        for record in records {
+            ratings.update(record);
+        }
+        ratings
+    }
+
+    pub fn get(&self, name: &str) -> Rating {
+        self.players.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn update(&mut self, record: &GameRecord) {
+        let rating1 = self.get(&record.player1);
+        let rating2 = self.get(&record.player2);
+        let score1 = match record.winner {
+            Some(Player::Player1) => 1.0,
+            Some(Player::Player2) => 0.0,
+            None => 0.5,
+        };
+        let expected1 = 1.0 / (1.0 + 10f64.powf((rating2.elo - rating1.elo) / 400.0));
+        let change = K_FACTOR * (score1 - expected1);
+
+        let player1 = self.players.entry(record.player1.clone()).or_default();
+        player1.elo += change;
+        count_result(player1, score1);
+        let player2 = self.players.entry(record.player2.clone()).or_default();
+        player2.elo -= change;
+        count_result(player2, 1.0 - score1);
+    }
+
+    /// Players sorted from the highest rating down.
+    pub fn leaderboard(&self) -> Vec<(&str, Rating)> {
+        let mut board: Vec<(&str, Rating)> = self
+            .players
+            .iter()
+            .map(|(name, rating)| (name.as_str(), *rating))
+            .collect();
+        board.sort_by(|a, b| b.1.elo.total_cmp(&a.1.elo));
+        board
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (name, rating) in &self.players {
+            writeln!(
+                writer,
+                "{}\t{:.2}\t{}\t{}\t{}",
+                name, rating.elo, rating.wins, rating.draws, rating.losses
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut ratings = Ratings::default();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let bad_line = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad rating line '{}'", line),
+                )
+            };
+            if fields.len() != 5 {
+                return Err(bad_line());
+            }
+            let rating = Rating {
+                elo: fields[1].parse().map_err(|_| bad_line())?,
+                wins: fields[2].parse().map_err(|_| bad_line())?,
+                draws: fields[3].parse().map_err(|_| bad_line())?,
+                losses: fields[4].parse().map_err(|_| bad_line())?,
+            };
+            ratings.players.insert(String::from(fields[0]), rating);
+        }
+        Ok(ratings)
+    }
+}
+
+/// Names are stored in tab-separated lines, so they can't be empty or hold
+/// tabs or line breaks.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(['\t', '\n', '\r']) {
+        return Err(format!("bad player name '{}'", name.escape_debug()));
+    }
+    Ok(())
+}
+
+fn count_result(rating: &mut Rating, score: f64) {
+    if score > 0.5 {
+        rating.wins += 1;
+    } else if score < 0.5 {
+        rating.losses += 1;
+    } else {
+        rating.draws += 1;
+    }
+}
+
+/// Ratings file that is rewritten after every game, optionally together with
+/// a records file the games are appended to.
+pub struct RatingStore {
+    path: PathBuf,
+    records_path: Option<PathBuf>,
+    ratings: Ratings,
+}
+
+impl RatingStore {
+    /// Opens `path`, starting from empty ratings if it doesn't exist yet.
+    pub fn open(path: PathBuf, records_path: Option<PathBuf>) -> io::Result<Self> {
+        let ratings = match File::open(&path) {
+            Ok(file) => Ratings::read_from(BufReader::new(file))?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ratings::default(),
+            Err(e) => return Err(e),
+        };
+        Ok(RatingStore {
+            path,
+            records_path,
+            ratings,
+        })
+    }
+
+    pub fn ratings(&self) -> &Ratings {
+        &self.ratings
+    }
+
+    pub fn record(&mut self, record: &GameRecord) -> io::Result<()> {
+        self.ratings.update(record);
+        self.ratings.write_to(&mut File::create(&self.path)?)?;
+        if let Some(records_path) = &self.records_path {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(records_path)?;
+            record::write_records(&mut file, std::slice::from_ref(record))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(player1: &str, player2: &str, winner: Option<Player>) -> GameRecord {
+        GameRecord {
+            player1: String::from(player1),
+            player2: String::from(player2),
+            first: Player::Player1,
+            moves: Vec::new(),
+            winner,
+        }
+    }
+
+    #[test]
+    fn winner_gains_what_loser_drops() {
+        let mut ratings = Ratings::default();
+        ratings.update(&record("alice", "random", Some(Player::Player1)));
+        assert_eq!(ratings.get("alice").elo, 1516.0);
+        assert_eq!(ratings.get("random").elo, 1484.0);
+        assert_eq!(ratings.get("random").losses, 1);
+        assert_eq!(ratings.leaderboard()[0].0, "alice");
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let ratings = Ratings::from_records(&[
+            record("perfect", "random", Some(Player::Player1)),
+            record("random", "perfect", None),
+        ]);
+        let mut bytes = Vec::new();
+        ratings.write_to(&mut bytes).unwrap();
+        let loaded = Ratings::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.get("random").games(), 2);
+        assert!((loaded.get("perfect").elo - ratings.get("perfect").elo).abs() < 0.01);
+        assert_eq!(check_name("ann lee"), Ok(()));
+        assert!(check_name("ann\tlee").is_err());
+        assert!(check_name("ann\n").is_err());
+    }
+}
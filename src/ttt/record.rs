@@ -0,0 +1,168 @@
+use std::io::{self, BufRead, Write};
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Player};
+
+/// A finished game, stored one per line as tab separated `key=value` fields
+/// (`player1`, `player2`, `first`, `winner` and comma separated `moves`), e.g.
+/// `first=1` and `winner=draw` or `winner=2`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub player1: String,
+    pub player2: String,
+    pub first: Player,
+    pub moves: Vec<usize>,
+    pub winner: Option<Player>,
+}
+
+impl GameRecord {
+    /// Board after each move, starting from the empty board.
+    pub fn replay(&self) -> Vec<Vec<Cell>> {
+        let mut cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+        let mut positions = vec![cells.clone()];
+        let mut side = self.first;
+        for &cell in &self.moves {
+            cells[cell] = Cell::Player(side);
+            positions.push(cells.clone());
+            side = side.opponent();
+        }
+        positions
+    }
+
+    pub fn to_line(&self) -> String {
+        let moves: Vec<String> = self.moves.iter().map(usize::to_string).collect();
+        format!(
+            "player1={}\tplayer2={}\tfirst={}\twinner={}\tmoves={}",
+            self.player1,
+            self.player2,
+            player_to_str(self.first),
+            self.winner.map_or("draw", player_to_str),
+            moves.join(",")
+        )
+    }
+
+    pub fn from_line(line: &str) -> io::Result<Self> {
+        let mut player1 = None;
+        let mut player2 = None;
+        let mut first = None;
+        let mut winner = None;
+        let mut moves = None;
+
+        for field in line.split('\t') {
+            let mut parts = field.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().ok_or_else(|| bad_record(line))?;
+            match key {
+                "player1" => player1 = Some(String::from(value)),
+                "player2" => player2 = Some(String::from(value)),
+                "first" => first = Some(player_from_str(value).ok_or_else(|| bad_record(line))?),
+                "winner" => {
+                    winner = Some(match value {
+                        "draw" => None,
+                        _ => Some(player_from_str(value).ok_or_else(|| bad_record(line))?),
+                    })
+                }
+                "moves" => {
+                    moves = Some(
+                        value
+                            .split(',')
+                            .filter(|m| !m.is_empty())
+                            .map(|m| m.parse().map_err(|_| bad_record(line)))
+                            .collect::<io::Result<Vec<usize>>>()?,
+                    )
+                }
+                // Unknown fields are left for newer versions of the format.
+                _ => (),
+            }
+        }
+
+        match (player1, player2, first, winner, moves) {
+            (Some(player1), Some(player2), Some(first), Some(winner), Some(moves))
+                if moves_are_legal(&moves) =>
+            {
+                Ok(GameRecord {
+                    player1,
+                    player2,
+                    first,
+                    moves,
+                    winner,
+                })
+            }
+            _ => Err(bad_record(line)),
+        }
+    }
+}
+
+pub fn write_records<W: Write>(writer: &mut W, records: &[GameRecord]) -> io::Result<()> {
+    for record in records {
+        writeln!(writer, "{}", record.to_line())?;
+    }
+    Ok(())
+}
+
+pub fn read_records<R: BufRead>(reader: R) -> io::Result<Vec<GameRecord>> {
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            records.push(GameRecord::from_line(line.trim_end())?);
+        }
+    }
+    Ok(records)
+}
+
+fn player_to_str(player: Player) -> &'static str {
+    match player {
+        Player::Player1 => "1",
+        Player::Player2 => "2",
+    }
+}
+
+fn player_from_str(s: &str) -> Option<Player> {
+    match s {
+        "1" => Some(Player::Player1),
+        "2" => Some(Player::Player2),
+        _ => None,
+    }
+}
+
+/// Each move on a cell of the board, none of them twice.
+fn moves_are_legal(moves: &[usize]) -> bool {
+    let mut taken = [false; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+    moves
+        .iter()
+        .all(|&cell| cell < taken.len() && !std::mem::replace(&mut taken[cell], true))
+}
+
+fn bad_record(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("bad game record '{}'", line),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_round_trip() {
+        let record = GameRecord {
+            player1: String::from("minimax:3"),
+            player2: String::from("random"),
+            first: Player::Player2,
+            moves: vec![4, 0, 8],
+            winner: Some(Player::Player1),
+        };
+        assert_eq!(GameRecord::from_line(&record.to_line()).unwrap(), record);
+        assert_eq!(record.replay()[3][8], Cell::Player(Player::Player2));
+    }
+
+    #[test]
+    fn moves_off_the_board_or_repeated_are_rejected() {
+        let line = "player1=a\tplayer2=b\tfirst=1\twinner=draw\tmoves=";
+        assert!(GameRecord::from_line(&format!("{}4,0", line)).is_ok());
+        assert!(GameRecord::from_line(&format!("{}4,9", line)).is_err());
+        assert!(GameRecord::from_line(&format!("{}4,0,4", line)).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use super::game::Player;
+
+/// Score of a best-of-N match between the human and the computer. The
+/// players take turns starting, the human first unless told otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    best_of: usize,
+    /// Who starts the first game.
+    first: Player,
+    /// Games won by `Player1` and `Player2`.
+    wins: [usize; 2],
+    draws: usize,
+}
+
+impl Series {
+    pub fn new(best_of: usize) -> Self {
+        Series::starting_with(best_of, Player::Player2)
+    }
+
+    pub fn starting_with(best_of: usize, first: Player) -> Self {
+        Series {
+            best_of,
+            first,
+            wins: [0, 0],
+            draws: 0,
+        }
+    }
+
+    pub fn first(&self) -> Player {
+        self.first
+    }
+
+    pub fn best_of(&self) -> usize {
+        self.best_of
+    }
+
+    pub fn wins(&self, player: Player) -> usize {
+        self.wins[player.index()]
+    }
+
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.wins[0] + self.wins[1] + self.draws
+    }
+
+    pub fn record(&mut self, winner: Option<Player>) {
+        match winner {
+            Some(player) => self.wins[player.index()] += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    /// Over once somebody has won more than half the games or all of them
+    /// have been played.
+    pub fn is_over(&self) -> bool {
+        self.wins.iter().any(|&wins| wins > self.best_of / 2) || self.games_played() >= self.best_of
+    }
+
+    /// Who won the match, `None` while it goes on or if it ended level.
+    pub fn winner(&self) -> Option<Player> {
+        if !self.is_over() {
+            return None;
+        }
+        let (computer, human) = (self.wins[0], self.wins[1]);
+        if computer > human {
+            Some(Player::Player1)
+        } else if human > computer {
+            Some(Player::Player2)
+        } else {
+            None
+        }
+    }
+
+    /// Who starts the next game.
+    pub fn next_first(&self) -> Player {
+        if self.games_played().is_multiple_of(2) {
+            self.first
+        } else {
+            self.first.opponent()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_of_three() {
+        let mut series = Series::new(3);
+        assert_eq!(series.next_first(), Player::Player2);
+        series.record(Some(Player::Player2));
+        assert_eq!(series.next_first(), Player::Player1);
+        series.record(None);
+        assert!(!series.is_over());
+        series.record(Some(Player::Player2));
+        assert!(series.is_over());
+        assert_eq!(series.winner(), Some(Player::Player2));
+
+        let mut series = Series::new(3);
+        for winner in &[Some(Player::Player1), Some(Player::Player2), None] {
+            series.record(*winner);
+        }
+        assert!(series.is_over());
+        assert_eq!(series.winner(), None);
+
+        let mut series = Series::starting_with(3, Player::Player1);
+        assert_eq!(series.next_first(), Player::Player1);
+        series.record(None);
+        assert_eq!(series.next_first(), Player::Player2);
+    }
+}
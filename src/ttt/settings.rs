@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::config::{
+    AI_DIFFICULTY, ANIMATION_SPEED, DEFAULT_THEME, SETTINGS_FILE, THEMES_DIR, VOLUME,
+};
+use super::game::{Mark, Player};
+use super::game_logic::Difficulty;
+use super::keys::{self, Command, KeyBindings, KeyPreset};
+
+use ggez::event::KeyCode;
+
+/// Animation speeds to choose from; 0 turns animations off.
+pub const ANIMATION_SPEEDS: [f32; 4] = [0.0, 0.5, 1.0, 2.0];
+
+/// Sound volumes in percent.
+pub const VOLUMES: [u8; 5] = [0, 25, 50, 75, 100];
+
+/// What the settings screen changes, kept between sessions as `key=value`
+/// lines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    /// Who moves first on the classic board.
+    pub first: Player,
+    /// The mark drawn for the human; the computer gets the other one.
+    pub human_mark: Mark,
+    pub theme: String,
+    /// Multiplies the speed of animations, 0 turns them off.
+    pub animation_speed: f32,
+    /// In percent.
+    pub volume: u8,
+    pub keys: KeyPreset,
+    /// Commands bound to other keys than the preset's, as `bind.<command>`
+    /// lines.
+    pub bindings: BTreeMap<Command, Vec<KeyCode>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            difficulty: AI_DIFFICULTY,
+            first: Player::Player2,
+            human_mark: Mark::Cross,
+            theme: String::from(DEFAULT_THEME),
+            animation_speed: ANIMATION_SPEED,
+            volume: VOLUME,
+            keys: KeyPreset::Arrows,
+            bindings: BTreeMap::new(),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/rust-tictactoe`, falling back to `~/.config` as the
+/// XDG spec says.
+fn config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("rust-tictactoe"))
+}
+
+/// `settings.conf` in the config directory.
+pub fn default_settings_path() -> Option<PathBuf> {
+    Some(config_dir()?.join(SETTINGS_FILE))
+}
+
+/// Where `<name>.theme` files are picked up from.
+pub fn default_themes_dir() -> Option<PathBuf> {
+    Some(config_dir()?.join(THEMES_DIR))
+}
+
+impl Settings {
+    /// Reads `key=value` lines; settings that aren't mentioned keep their
+    /// defaults. A binding to a command or key this version doesn't know is
+    /// skipped with a warning rather than losing the other settings.
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut settings = Settings::default();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad_setting = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad setting '{}'", line),
+                )
+            };
+            let (key, value) = line.split_once('=').ok_or_else(bad_setting)?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "difficulty" => settings.difficulty = value.parse().map_err(|_| bad_setting())?,
+                "first" => {
+                    settings.first = match value {
+                        "player" => Player::Player2,
+                        "computer" => Player::Player1,
+                        _ => return Err(bad_setting()),
+                    }
+                }
+                "mark" => {
+                    settings.human_mark = match value {
+                        "x" => Mark::Cross,
+                        "o" => Mark::Nought,
+                        _ => return Err(bad_setting()),
+                    }
+                }
+                "theme" => settings.theme = value.to_string(),
+                "animation" => {
+                    settings.animation_speed = value
+                        .parse()
+                        .ok()
+                        .filter(|&speed: &f32| speed >= 0.0)
+                        .ok_or_else(bad_setting)?
+                }
+                "volume" => {
+                    settings.volume = value
+                        .parse()
+                        .ok()
+                        .filter(|&volume| volume <= 100)
+                        .ok_or_else(bad_setting)?
+                }
+                "keys" => settings.keys = value.parse().map_err(|_| bad_setting())?,
+                _ => {
+                    let command = key.strip_prefix("bind.").ok_or_else(bad_setting)?;
+                    let binding = command.parse::<Command>().and_then(|command| {
+                        let keys = value
+                            .split(',')
+                            .map(|name| keys::parse_key(name.trim()))
+                            .collect::<Result<_, _>>()?;
+                        Ok((command, keys))
+                    });
+                    match binding {
+                        Ok((command, keys)) => {
+                            settings.bindings.insert(command, keys);
+                        }
+                        Err(e) => eprintln!("Skipping setting '{}': {}", line, e),
+                    }
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "# rust-tictactoe settings")?;
+        writeln!(writer, "difficulty={}", self.difficulty)?;
+        let first = match self.first {
+            Player::Player1 => "computer",
+            Player::Player2 => "player",
+        };
+        writeln!(writer, "first={}", first)?;
+        let mark = match self.human_mark {
+            Mark::Cross => "x",
+            Mark::Nought => "o",
+        };
+        writeln!(writer, "mark={}", mark)?;
+        writeln!(writer, "theme={}", self.theme)?;
+        writeln!(writer, "animation={}", self.animation_speed)?;
+        writeln!(writer, "volume={}", self.volume)?;
+        writeln!(writer, "keys={}", self.keys)?;
+        for (command, keys) in &self.bindings {
+            let keys: Vec<String> = keys.iter().map(|&key| keys::key_name(key)).collect();
+            writeln!(writer, "bind.{}={}", command.name(), keys.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// The preset with the bindings of the settings on top.
+    pub fn key_bindings(&self) -> KeyBindings {
+        let mut bindings = KeyBindings::preset(self.keys);
+        for (&command, keys) in &self.bindings {
+            bindings.bind(command, keys.clone());
+        }
+        bindings
+    }
+
+    /// The settings saved at `path`, the defaults if there are none yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match File::open(path) {
+            Ok(file) => Settings::read_from(BufReader::new(file)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        self.write_to(&mut File::create(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings {
+            difficulty: Difficulty::Easy,
+            first: Player::Player1,
+            human_mark: Mark::Nought,
+            animation_speed: 0.5,
+            volume: 25,
+            keys: KeyPreset::Vim,
+            ..Settings::default()
+        };
+        settings
+            .bindings
+            .insert(Command::Restart, vec![KeyCode::F5, KeyCode::N]);
+        let mut bytes = Vec::new();
+        settings.write_to(&mut bytes).unwrap();
+        assert_eq!(Settings::read_from(bytes.as_slice()).unwrap(), settings);
+
+        let partial = Settings::read_from("# mine\nvolume = 0\n".as_bytes()).unwrap();
+        assert_eq!(partial.volume, 0);
+        assert_eq!(partial.keys, KeyPreset::Arrows);
+        assert!(Settings::read_from("volume=200".as_bytes()).is_err());
+        assert!(Settings::read_from("colour=red".as_bytes()).is_err());
+
+        let bindings = Settings::read_from("bind.place=Hyper\nbind.menu=Q\nvolume=0\n".as_bytes())
+            .unwrap()
+            .bindings;
+        assert_eq!(bindings.get(&Command::Place), None);
+        assert_eq!(bindings.get(&Command::Menu), Some(&vec![KeyCode::Q]));
+        assert!(Settings::read_from("bind.jump=J".as_bytes()).is_ok());
+    }
+}
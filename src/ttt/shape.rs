@@ -0,0 +1,122 @@
+use std::io::{self, BufRead};
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Topology};
+
+const CELL_COUNT: usize = PLAY_FIELD_SIZE * PLAY_FIELD_SIZE;
+
+/// Classic board with some cells blocked and, optionally, its own set of
+/// winning lines instead of the rows, columns and diagonals.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardShape {
+    blocked: Vec<usize>,
+    lines: Option<Vec<Vec<usize>>>,
+}
+
+impl BoardShape {
+    pub fn is_blocked(&self, id: usize) -> bool {
+        self.blocked.contains(&id)
+    }
+
+    pub fn blocked(&self) -> &[usize] {
+        &self.blocked
+    }
+
+    pub fn has_own_lines(&self) -> bool {
+        self.lines.is_some()
+    }
+
+    /// The lines that win on this board. Lines of `topology` running through
+    /// a blocked cell can never be completed, so they need no filtering.
+    pub fn lines(&self, topology: Topology) -> &[Vec<usize>] {
+        self.lines.as_deref().unwrap_or_else(|| topology.lines())
+    }
+
+    /// Board at the start of a game.
+    pub fn empty_cells(&self) -> Vec<Cell> {
+        (0..CELL_COUNT)
+            .map(|id| {
+                if self.is_blocked(id) {
+                    Cell::Blocked
+                } else {
+                    Cell::Empty
+                }
+            })
+            .collect()
+    }
+
+    /// Reads a shape: `PLAY_FIELD_SIZE` rows of `.` for a playable and `*`
+    /// for a blocked cell, then any number of `line <cell> <cell> ...` entries
+    /// with cells numbered row by row from 0. `#` starts a comment.
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut shape = BoardShape::default();
+        let mut rows = 0;
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(cells) = line.strip_prefix("line ") {
+                let cells = cells
+                    .split_whitespace()
+                    .map(|cell| cell.parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| bad_shape(line))?;
+                shape.lines.get_or_insert_with(Vec::new).push(cells);
+                continue;
+            }
+
+            if rows == PLAY_FIELD_SIZE || line.chars().count() != PLAY_FIELD_SIZE {
+                return Err(bad_shape(line));
+            }
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '.' => (),
+                    '*' => shape.blocked.push(x + rows * PLAY_FIELD_SIZE),
+                    _ => return Err(bad_shape(line)),
+                }
+            }
+            rows += 1;
+        }
+        if rows != PLAY_FIELD_SIZE {
+            return Err(bad_shape("board needs one row per line of the grid"));
+        }
+
+        for line in shape.lines.iter().flatten() {
+            let playable = line
+                .iter()
+                .all(|&id| id < CELL_COUNT && !shape.is_blocked(id));
+            let distinct = (1..line.len()).all(|i| !line[..i].contains(&line[i]));
+            if line.len() < 2 || !playable || !distinct {
+                return Err(bad_shape(&format!("bad line {:?}", line)));
+            }
+        }
+        Ok(shape)
+    }
+}
+
+fn bad_shape(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("bad board shape '{}'", line),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_blocked_cells_and_lines() {
+        let file = "# corners out\n*.*\n...\n*.*\nline 1 4 7\nline 3 4 5 # the cross\n";
+        let shape = BoardShape::read_from(file.as_bytes()).unwrap();
+        assert_eq!(shape.blocked(), &[0, 2, 6, 8]);
+        assert_eq!(shape.lines(Topology::Flat), &[vec![1, 4, 7], vec![3, 4, 5]]);
+        assert_eq!(shape.empty_cells()[2], Cell::Blocked);
+
+        assert!(BoardShape::read_from("*.*\n...\n".as_bytes()).is_err());
+        assert!(BoardShape::read_from("*..\n...\n...\nline 0 1\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use ggez::{
+    graphics::{self, Color, DrawParam, Image, Rect},
+    nalgebra::{Point2, Vector2},
+    Context, GameResult,
+};
+
+use super::config::SCREEN_SIZE;
+use super::game::Mark;
+use super::theme::Theme;
+
+/// The images of the current theme, loaded once it is drawn with.
+#[derive(Default)]
+pub(crate) struct Skin {
+    /// Board, nought and cross image paths the images came from.
+    paths: Vec<Option<String>>,
+    board: Option<Image>,
+    nought: Option<Image>,
+    cross: Option<Image>,
+    /// Mark images to draw under the lines of the frame.
+    sprites: Vec<(Image, Rect, Color)>,
+}
+
+/// The image at `path` in ggez's resource filesystem, none if there is no
+/// path or it can't be loaded.
+fn load(ctx: &mut Context, path: &Option<String>) -> Option<Image> {
+    let path = path.as_ref()?;
+    match Image::new(ctx, path) {
+        Ok(image) => Some(image),
+        Err(e) => {
+            eprintln!("Can't load image '{}': {}", path, e);
+            None
+        }
+    }
+}
+
+impl Skin {
+    /// Picks up the images of a newly chosen `theme` and draws its board
+    /// image over the whole window. Starts each frame.
+    pub(crate) fn draw_board(&mut self, ctx: &mut Context, theme: &Theme) {
+        let paths = vec![
+            theme.board_image.clone(),
+            theme.nought_image.clone(),
+            theme.cross_image.clone(),
+        ];
+        if self.paths != paths {
+            self.board = load(ctx, &paths[0]);
+            self.nought = load(ctx, &paths[1]);
+            self.cross = load(ctx, &paths[2]);
+            self.paths = paths;
+        }
+        self.sprites.clear();
+        if let Some(board) = &self.board {
+            let scale = Vector2::new(
+                SCREEN_SIZE.0 / board.width() as f32,
+                SCREEN_SIZE.1 / board.height() as f32,
+            );
+            let _ = graphics::draw(ctx, board, DrawParam::default().scale(scale));
+        }
+    }
+
+    /// Queues the image of `mark` to fill `rect`, tinted with `color`. False
+    /// if the theme has none, and the mark is drawn with lines instead.
+    pub(crate) fn queue_mark(&mut self, mark: Mark, rect: Rect, color: Color) -> bool {
+        let image = match mark {
+            Mark::Nought => &self.nought,
+            Mark::Cross => &self.cross,
+        };
+        match image {
+            Some(image) => {
+                self.sprites.push((image.clone(), rect, color));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draws the queued mark images; goes before the frame's mesh so the grid
+    /// and the winning line stay on top.
+    pub(crate) fn draw_sprites(&mut self, ctx: &mut Context) -> GameResult {
+        for (image, rect, color) in self.sprites.drain(..) {
+            let scale = Vector2::new(
+                rect.w / image.width() as f32,
+                rect.h / image.height() as f32,
+            );
+            graphics::draw(
+                ctx,
+                &image,
+                DrawParam::default()
+                    .dest(Point2::new(rect.x, rect.y))
+                    .scale(scale)
+                    .color(color),
+            )?;
+        }
+        Ok(())
+    }
+}
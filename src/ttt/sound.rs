@@ -0,0 +1,30 @@
+use ggez::audio::SoundData;
+
+const SAMPLE_RATE: u32 = 22_050;
+
+/// A sine tone of `frequency` Hz fading out over `millis`, as a mono 16 bit
+/// WAV, so the game needs no sound files.
+pub(crate) fn tone(frequency: f32, millis: u32) -> SoundData {
+    let samples = SAMPLE_RATE * millis / 1000;
+    let mut wav = Vec::with_capacity(44 + 2 * samples as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + 2 * samples).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel.
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(2 * SAMPLE_RATE).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(2 * samples).to_le_bytes());
+    for i in 0..samples {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let fade = 1.0 - i as f32 / samples as f32;
+        let sample = (t * frequency * std::f32::consts::TAU).sin() * fade * 0.5;
+        wav.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    SoundData::from_bytes(&wav)
+}
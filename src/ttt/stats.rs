@@ -0,0 +1,382 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::config::{HISTORY_FILE, PLAY_FIELD_SIZE};
+use super::game::Player;
+use super::game_logic::Difficulty;
+
+/// How a game ended for the human.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl GameOutcome {
+    pub fn from_winner(winner: Option<Player>) -> Self {
+        match winner {
+            Some(Player::Player2) => GameOutcome::Win,
+            Some(Player::Player1) => GameOutcome::Loss,
+            None => GameOutcome::Draw,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            GameOutcome::Win => "win",
+            GameOutcome::Draw => "draw",
+            GameOutcome::Loss => "loss",
+        }
+    }
+}
+
+/// A finished game against the computer, stored one per line as tab
+/// separated `key=value` fields like `GameRecord`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// UTC, as `2024-01-31T12:00:00Z`.
+    pub date: String,
+    pub mode: String,
+    pub opponent: String,
+    pub difficulty: Difficulty,
+    pub outcome: GameOutcome,
+    pub first: Player,
+    pub moves: Vec<usize>,
+}
+
+impl HistoryEntry {
+    pub fn to_line(&self) -> String {
+        let moves: Vec<String> = self.moves.iter().map(usize::to_string).collect();
+        format!(
+            "date={}\tmode={}\topponent={}\tdifficulty={}\tresult={}\tfirst={}\tmoves={}",
+            self.date,
+            self.mode,
+            self.opponent,
+            self.difficulty,
+            self.outcome.name(),
+            if self.first == Player::Player1 { 1 } else { 2 },
+            moves.join(",")
+        )
+    }
+
+    pub fn from_line(line: &str) -> io::Result<Self> {
+        let bad_entry = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bad history entry '{}'", line),
+            )
+        };
+        let mut fields = BTreeMap::new();
+        for field in line.split('\t') {
+            let (key, value) = field.split_once('=').ok_or_else(bad_entry)?;
+            fields.insert(key, value);
+        }
+        let field = |key: &str| fields.get(key).copied().ok_or_else(bad_entry);
+
+        let outcome = match field("result")? {
+            "win" => GameOutcome::Win,
+            "draw" => GameOutcome::Draw,
+            "loss" => GameOutcome::Loss,
+            _ => return Err(bad_entry()),
+        };
+        let first = match field("first")? {
+            "1" => Player::Player1,
+            "2" => Player::Player2,
+            _ => return Err(bad_entry()),
+        };
+        let moves = field("moves")?;
+        let moves = if moves.is_empty() {
+            Vec::new()
+        } else {
+            moves
+                .split(',')
+                .map(|m| m.parse().map_err(|_| bad_entry()))
+                .collect::<io::Result<_>>()?
+        };
+        Ok(HistoryEntry {
+            date: field("date")?.to_string(),
+            mode: field("mode")?.to_string(),
+            opponent: field("opponent")?.to_string(),
+            difficulty: field("difficulty")?.parse().map_err(|_| bad_entry())?,
+            outcome,
+            first,
+            moves,
+        })
+    }
+
+    /// The first move of the human, if they started.
+    fn opening(&self) -> Option<usize> {
+        if self.first == Player::Player2 {
+            self.moves.first().copied()
+        } else {
+            None
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/rust-tictactoe/history.tsv`, falling back to
+/// `~/.local/share` as the XDG spec says.
+pub fn default_history_path() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+        })?;
+    Some(data_home.join("rust-tictactoe").join(HISTORY_FILE))
+}
+
+/// History file that every finished game is appended to.
+pub struct History {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Opens `path`, starting with no games if it doesn't exist yet.
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let entries = match File::open(&path) {
+            Ok(file) => read_entries(BufReader::new(file))?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(History { path, entries })
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", entry.to_line())?;
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+/// The games in `reader`, one per line. A line that can't be read is skipped
+/// with a warning rather than losing the whole history.
+fn read_entries<R: BufRead>(reader: R) -> io::Result<Vec<HistoryEntry>> {
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match HistoryEntry::from_line(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("Skipping game history line: {}", e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Cell name with columns `a` to `c` and rows 1 to 3 from the top.
+pub fn cell_name(id: usize) -> String {
+    let column = (b'a' + (id % PLAY_FIELD_SIZE) as u8) as char;
+    format!("{}{}", column, id / PLAY_FIELD_SIZE + 1)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One row per game, moves as space separated cell names.
+pub fn write_csv<W: Write>(writer: &mut W, entries: &[HistoryEntry]) -> io::Result<()> {
+    writeln!(writer, "date,mode,opponent,difficulty,result,first,moves")?;
+    for entry in entries {
+        let moves: Vec<String> = entry.moves.iter().map(|&id| cell_name(id)).collect();
+        let first = match entry.first {
+            Player::Player1 => "computer",
+            Player::Player2 => "player",
+        };
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            csv_field(&entry.date),
+            csv_field(&entry.mode),
+            csv_field(&entry.opponent),
+            entry.difficulty,
+            entry.outcome.name(),
+            first,
+            moves.join(" ")
+        )?;
+    }
+    Ok(())
+}
+
+/// The current time as `2024-01-31T12:00:00Z`.
+pub fn now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_date(seconds)
+}
+
+/// Seconds since the epoch as a UTC date, after Howard Hinnant's
+/// `civil_from_days`.
+fn format_date(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    let time = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Tally {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Tally {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Share of the games in percent.
+    pub fn percent(&self, count: usize) -> f32 {
+        if self.games() == 0 {
+            0.0
+        } else {
+            100.0 * count as f32 / self.games() as f32
+        }
+    }
+}
+
+/// What the statistics screen shows.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub per_difficulty: BTreeMap<Difficulty, Tally>,
+    /// Outcome of the latest games and how many in a row ended that way.
+    pub current_streak: Option<(GameOutcome, usize)>,
+    pub longest_winning_streak: usize,
+    /// The human's first moves with how often each was played, most common
+    /// first.
+    pub openings: Vec<(usize, usize)>,
+}
+
+impl Statistics {
+    pub fn from_entries(entries: &[HistoryEntry]) -> Self {
+        let mut statistics = Statistics::default();
+        let mut winning_streak = 0;
+        let mut openings = BTreeMap::new();
+        for entry in entries {
+            let tally = statistics
+                .per_difficulty
+                .entry(entry.difficulty)
+                .or_default();
+            match entry.outcome {
+                GameOutcome::Win => tally.wins += 1,
+                GameOutcome::Draw => tally.draws += 1,
+                GameOutcome::Loss => tally.losses += 1,
+            }
+
+            statistics.current_streak = match statistics.current_streak {
+                Some((outcome, count)) if outcome == entry.outcome => Some((outcome, count + 1)),
+                _ => Some((entry.outcome, 1)),
+            };
+            winning_streak = if entry.outcome == GameOutcome::Win {
+                winning_streak + 1
+            } else {
+                0
+            };
+            statistics.longest_winning_streak =
+                statistics.longest_winning_streak.max(winning_streak);
+
+            if let Some(cell) = entry.opening() {
+                *openings.entry(cell).or_insert(0) += 1;
+            }
+        }
+        statistics.openings = openings.into_iter().collect();
+        statistics
+            .openings
+            .sort_by_key(|&(cell, count)| (std::cmp::Reverse(count), cell));
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(difficulty: Difficulty, outcome: GameOutcome, moves: Vec<usize>) -> HistoryEntry {
+        HistoryEntry {
+            date: format_date(1_700_000_000),
+            mode: String::from("classic"),
+            opponent: String::from("perfect"),
+            difficulty,
+            outcome,
+            first: Player::Player2,
+            moves,
+        }
+    }
+
+    #[test]
+    fn line_round_trip() {
+        let entry = entry(Difficulty::Medium, GameOutcome::Loss, vec![4, 0, 8]);
+        assert_eq!(entry.date, "2023-11-14T22:13:20Z");
+        assert_eq!(HistoryEntry::from_line(&entry.to_line()).unwrap(), entry);
+        let file = format!("{}\nnot a game\n\n{}\n", entry.to_line(), entry.to_line());
+        assert_eq!(
+            read_entries(file.as_bytes()).unwrap(),
+            vec![entry.clone(), entry.clone()]
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &[entry]).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("2023-11-14T22:13:20Z,classic,perfect,medium,loss,player,b2 a1 c3")
+        );
+    }
+
+    #[test]
+    fn streaks_and_openings() {
+        let entries = vec![
+            entry(Difficulty::Easy, GameOutcome::Win, vec![4]),
+            entry(Difficulty::Easy, GameOutcome::Win, vec![0]),
+            entry(Difficulty::Hard, GameOutcome::Draw, vec![4]),
+            entry(Difficulty::Easy, GameOutcome::Win, vec![4]),
+        ];
+        let statistics = Statistics::from_entries(&entries);
+        let easy = statistics.per_difficulty[&Difficulty::Easy];
+        assert_eq!((easy.wins, easy.draws, easy.losses), (3, 0, 0));
+        assert_eq!(statistics.current_streak, Some((GameOutcome::Win, 1)));
+        assert_eq!(statistics.longest_winning_streak, 2);
+        assert_eq!(statistics.openings, vec![(4, 3), (0, 1)]);
+    }
+}
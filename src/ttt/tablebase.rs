@@ -0,0 +1,250 @@
+use std::io::{self, Read, Write};
+use std::sync::OnceLock;
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Game, GameState, Player};
+
+const CELL_COUNT: usize = PLAY_FIELD_SIZE * PLAY_FIELD_SIZE;
+const POSITION_COUNT: usize = 19683; // 3^9
+const MAGIC: &[u8; 4] = b"TTTB";
+const VERSION: u8 = 1;
+const ILLEGAL: u8 = 0xFF;
+
+static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+
+/// Result of a position for the side to move, assuming perfect play.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub outcome: Outcome,
+    /// Number of plies until the game ends when both sides play perfectly:
+    /// the winner hurries, the loser delays.
+    pub distance: u8,
+}
+
+impl Entry {
+    fn score(self) -> i32 {
+        match self.outcome {
+            Outcome::Win => 100 - i32::from(self.distance),
+            Outcome::Draw => 0,
+            Outcome::Loss => -100 + i32::from(self.distance),
+        }
+    }
+
+    fn from_child(child: Entry) -> Entry {
+        let outcome = match child.outcome {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        };
+        Entry {
+            outcome,
+            distance: child.distance + 1,
+        }
+    }
+
+    fn encode(self) -> u8 {
+        let outcome = match self.outcome {
+            Outcome::Loss => 0,
+            Outcome::Draw => 1,
+            Outcome::Win => 2,
+        };
+        outcome << 4 | self.distance
+    }
+
+    fn decode(byte: u8) -> io::Result<Option<Entry>> {
+        if byte == ILLEGAL {
+            return Ok(None);
+        }
+        let outcome = match byte >> 4 {
+            0 => Outcome::Loss,
+            1 => Outcome::Draw,
+            2 => Outcome::Win,
+            _ => return Err(invalid_data("bad outcome in tablebase entry")),
+        };
+        Ok(Some(Entry {
+            outcome,
+            distance: byte & 0x0F,
+        }))
+    }
+}
+
+/// Every position reachable on the 3x3 board, for both sides to move.
+pub struct Tablebase {
+    entries: Vec<Option<Entry>>,
+}
+
+impl Tablebase {
+    /// Enumerates all legal positions starting from the empty board with either
+    /// player moving first.
+    pub fn solve() -> Self {
+        let mut tablebase = Tablebase {
+            entries: vec![None; POSITION_COUNT * 2],
+        };
+        let mut cells = vec![Cell::Empty; CELL_COUNT];
+        tablebase.solve_position(&mut cells, Player::Player1);
+        tablebase.solve_position(&mut cells, Player::Player2);
+        tablebase
+    }
+
+    fn solve_position(&mut self, cells: &mut [Cell], side: Player) -> Entry {
+        let index = position_key(cells, side);
+        if let Some(entry) = self.entries[index] {
+            return entry;
+        }
+
+        let entry = match Game::get_game_state(cells) {
+            GameState::InProgress => {
+                let mut best: Option<Entry> = None;
+                for i in 0..cells.len() {
+                    if cells[i] == Cell::Empty {
+                        cells[i] = Cell::Player(side);
+                        let child = self.solve_position(cells, side.opponent());
+                        cells[i] = Cell::Empty;
+
+                        let candidate = Entry::from_child(child);
+                        if best.is_none_or(|b| candidate.score() > b.score()) {
+                            best = Some(candidate);
+                        }
+                    }
+                }
+                best.expect("position in progress has an empty cell")
+            }
+            state => Entry {
+                outcome: match state.winner() {
+                    Some(player) if player == side => Outcome::Win,
+                    Some(_) => Outcome::Loss,
+                    None => Outcome::Draw,
+                },
+                distance: 0,
+            },
+        };
+
+        self.entries[index] = Some(entry);
+        entry
+    }
+
+    /// Value of `cells` for `side` to move, or `None` if the position can't
+    /// be reached in a real game.
+    pub fn lookup(&self, cells: &[Cell], side: Player) -> Option<Entry> {
+        if cells.len() != CELL_COUNT {
+            return None;
+        }
+        self.entries[position_key(cells, side)]
+    }
+
+    /// All moves for `side` that share the best tablebase value.
+    pub fn best_moves(&self, cells: &[Cell], side: Player) -> Vec<usize> {
+        let mut cells = cells.to_vec();
+        let mut best_score = i32::MIN;
+        let mut best_moves = Vec::new();
+        if self.lookup(&cells, side).is_none() {
+            return best_moves;
+        }
+
+        for i in 0..cells.len() {
+            if cells[i] == Cell::Empty {
+                cells[i] = Cell::Player(side);
+                let child = self.lookup(&cells, side.opponent());
+                cells[i] = Cell::Empty;
+
+                if let Some(child) = child {
+                    let score = Entry::from_child(child).score();
+                    if score > best_score {
+                        best_score = score;
+                        best_moves.clear();
+                    }
+                    if score == best_score {
+                        best_moves.push(i);
+                    }
+                }
+            }
+        }
+        best_moves
+    }
+
+    /// Writes the tablebase as a magic header followed by one byte per entry.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        let bytes: Vec<u8> = self
+            .entries
+            .iter()
+            .map(|entry| entry.map_or(ILLEGAL, Entry::encode))
+            .collect();
+        writer.write_all(&bytes)
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC || header[4] != VERSION {
+            return Err(invalid_data("not a tablebase file"));
+        }
+        let mut bytes = vec![0u8; POSITION_COUNT * 2];
+        reader.read_exact(&mut bytes)?;
+        let entries = bytes
+            .into_iter()
+            .map(Entry::decode)
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Tablebase { entries })
+    }
+}
+
+/// Shared tablebase, solved on first use unless one was installed before.
+pub fn global() -> &'static Tablebase {
+    TABLEBASE.get_or_init(Tablebase::solve)
+}
+
+pub fn install(tablebase: Tablebase) {
+    let _ = TABLEBASE.set(tablebase);
+}
+
+/// Unique key of a position and side to move, also used by the opening book.
+pub(crate) fn position_key(cells: &[Cell], side: Player) -> usize {
+    let position = cells.iter().rev().fold(0, |acc, cell| {
+        acc * 3
+            + match cell {
+                // Only whole boards are solved.
+                Cell::Empty | Cell::Blocked => 0,
+                Cell::Player(Player::Player1) => 1,
+                Cell::Player(Player::Player2) => 2,
+            }
+    });
+    position * 2 + side.index()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_board_is_draw() {
+        let tablebase = Tablebase::solve();
+        let cells = vec![Cell::Empty; CELL_COUNT];
+        for &side in &[Player::Player1, Player::Player2] {
+            let entry = tablebase.lookup(&cells, side).unwrap();
+            assert_eq!(entry.outcome, Outcome::Draw);
+            assert_eq!(entry.distance, 9);
+        }
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let tablebase = Tablebase::solve();
+        let mut bytes = Vec::new();
+        tablebase.write_to(&mut bytes).unwrap();
+        let loaded = Tablebase::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(tablebase.entries, loaded.entries);
+    }
+}
@@ -0,0 +1,251 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use super::game::Mark;
+
+/// How marks are drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PieceStyle {
+    Outline,
+    /// Solid noughts and bold crosses.
+    Filled,
+}
+
+/// Colours as `0xRRGGBB` and line widths the boards are drawn with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub background: u32,
+    pub grid: u32,
+    pub blocked: u32,
+    /// Marks of the computer and of the human.
+    pub player1: u32,
+    pub player2: u32,
+    /// Marks about to vanish and numbers already used.
+    pub faded: u32,
+    pub cursor: u32,
+    pub win_line: u32,
+    /// The boards that may be played in, in ultimate.
+    pub highlight: u32,
+    pub text: u32,
+    /// Symbols of the seats in multi, the human's first.
+    pub seats: [u32; 4],
+    pub grid_width: f32,
+    pub mark_width: f32,
+    pub win_line_width: f32,
+    pub pieces: PieceStyle,
+    /// Images in ggez's resource filesystem drawn over the whole window and
+    /// instead of each mark, whoever plays it; lines are drawn where there
+    /// are none.
+    pub board_image: Option<String>,
+    pub nought_image: Option<String>,
+    pub cross_image: Option<String>,
+}
+
+impl Theme {
+    pub const CLASSIC: Theme = Theme {
+        background: 0xB0B0B0,
+        grid: 0x000000,
+        blocked: 0x505050,
+        player1: 0x000000,
+        player2: 0x000000,
+        faded: 0x707070,
+        cursor: 0xFF0000,
+        win_line: 0xFF0000,
+        highlight: 0x008000,
+        text: 0x000000,
+        seats: [0x000000, 0x0000A0, 0xA00000, 0x006000],
+        grid_width: 4.0,
+        mark_width: 4.0,
+        win_line_width: 10.0,
+        pieces: PieceStyle::Outline,
+        board_image: None,
+        nought_image: None,
+        cross_image: None,
+    };
+
+    pub const DARK: Theme = Theme {
+        background: 0x1E1E1E,
+        grid: 0x808080,
+        blocked: 0x3A3A3A,
+        player1: 0x4FC3F7,
+        player2: 0xFFB74D,
+        faded: 0x5A5A5A,
+        cursor: 0xE57373,
+        win_line: 0xEF5350,
+        highlight: 0x66BB6A,
+        text: 0xE0E0E0,
+        seats: [0xE0E0E0, 0x4FC3F7, 0xFFB74D, 0x81C784],
+        grid_width: 4.0,
+        mark_width: 4.0,
+        win_line_width: 10.0,
+        pieces: PieceStyle::Outline,
+        board_image: None,
+        nought_image: None,
+        cross_image: None,
+    };
+
+    pub const HIGH_CONTRAST: Theme = Theme {
+        background: 0x000000,
+        grid: 0xFFFFFF,
+        blocked: 0x808080,
+        player1: 0x00FFFF,
+        player2: 0xFFFF00,
+        faded: 0x808080,
+        cursor: 0xFF00FF,
+        win_line: 0x00FF00,
+        highlight: 0x00FF00,
+        text: 0xFFFFFF,
+        seats: [0xFFFFFF, 0x00FFFF, 0xFFFF00, 0x00FF00],
+        grid_width: 6.0,
+        mark_width: 6.0,
+        win_line_width: 12.0,
+        pieces: PieceStyle::Filled,
+        board_image: None,
+        nought_image: None,
+        cross_image: None,
+    };
+
+    /// The image `mark` is drawn with, if any.
+    pub fn mark_image(&self, mark: Mark) -> Option<&str> {
+        match mark {
+            Mark::Nought => self.nought_image.as_deref(),
+            Mark::Cross => self.cross_image.as_deref(),
+        }
+    }
+
+    /// Reads `key=value` lines, colours written as `#RRGGBB`; whatever isn't
+    /// mentioned stays as in the classic theme.
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut theme = Theme::CLASSIC;
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad_line = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad theme line '{}'", line),
+                )
+            };
+            let (key, value) = line.split_once('=').ok_or_else(bad_line)?;
+            let (key, value) = (key.trim(), value.trim());
+            let color = || {
+                value
+                    .strip_prefix('#')
+                    .filter(|hex| hex.len() == 6)
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .ok_or_else(bad_line)
+            };
+            let image = || {
+                if value.starts_with('/') {
+                    Ok(Some(value.to_string()))
+                } else {
+                    Err(bad_line())
+                }
+            };
+            let width = || {
+                value
+                    .parse()
+                    .ok()
+                    .filter(|&width: &f32| width > 0.0)
+                    .ok_or_else(bad_line)
+            };
+            match key {
+                "background" => theme.background = color()?,
+                "grid" => theme.grid = color()?,
+                "blocked" => theme.blocked = color()?,
+                "player1" => theme.player1 = color()?,
+                "player2" => theme.player2 = color()?,
+                "faded" => theme.faded = color()?,
+                "cursor" => theme.cursor = color()?,
+                "win_line" => theme.win_line = color()?,
+                "highlight" => theme.highlight = color()?,
+                "text" => theme.text = color()?,
+                "seat1" => theme.seats[0] = color()?,
+                "seat2" => theme.seats[1] = color()?,
+                "seat3" => theme.seats[2] = color()?,
+                "seat4" => theme.seats[3] = color()?,
+                "grid_width" => theme.grid_width = width()?,
+                "mark_width" => theme.mark_width = width()?,
+                "win_line_width" => theme.win_line_width = width()?,
+                "pieces" => {
+                    theme.pieces = match value {
+                        "outline" => PieceStyle::Outline,
+                        "filled" => PieceStyle::Filled,
+                        _ => return Err(bad_line()),
+                    }
+                }
+                "board_image" => theme.board_image = image()?,
+                "nought_image" => theme.nought_image = image()?,
+                "cross_image" => theme.cross_image = image()?,
+                _ => return Err(bad_line()),
+            }
+        }
+        Ok(theme)
+    }
+}
+
+/// The themes that come with the game, by name.
+pub fn builtin() -> Vec<(String, Theme)> {
+    vec![
+        (String::from("classic"), Theme::CLASSIC),
+        (String::from("dark"), Theme::DARK),
+        (String::from("high-contrast"), Theme::HIGH_CONTRAST),
+    ]
+}
+
+/// Every `<name>.theme` file in `dir`, none if it doesn't exist. A file that
+/// can't be read is skipped with a warning.
+pub fn load_themes(dir: &Path) -> io::Result<Vec<(String, Theme)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut themes = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "theme")
+        {
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                match File::open(&path).and_then(|file| Theme::read_from(BufReader::new(file))) {
+                    Ok(theme) => themes.push((name.to_string(), theme)),
+                    Err(e) => eprintln!("Can't read theme '{}': {}", path.display(), e),
+                }
+            }
+        }
+    }
+    themes.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(themes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_files_override_the_classic_theme() {
+        let theme =
+            Theme::read_from("# night\nbackground = #102030\npieces=filled\n".as_bytes()).unwrap();
+        assert_eq!(theme.background, 0x102030);
+        assert_eq!(theme.pieces, PieceStyle::Filled);
+        assert_eq!(theme.grid, Theme::CLASSIC.grid);
+        assert_eq!(theme.cross_image, None);
+        let seats = Theme::read_from("seat3=#FF8000".as_bytes()).unwrap().seats;
+        assert_eq!(seats, [0x000000, 0x0000A0, 0xFF8000, 0x006000]);
+
+        let skin = Theme::read_from("nought_image=/wood/o.png".as_bytes()).unwrap();
+        assert_eq!(skin.mark_image(Mark::Nought), Some("/wood/o.png"));
+        assert!(Theme::read_from("player1_image=/wood/o.png".as_bytes()).is_err());
+        assert!(Theme::read_from("board_image=wood.png".as_bytes()).is_err());
+
+        assert!(Theme::read_from("grid=black".as_bytes()).is_err());
+        assert!(Theme::read_from("mark_width=0".as_bytes()).is_err());
+        assert!(Theme::read_from("shadow=#000000".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use super::config::PLAY_FIELD_SIZE;
+use super::engine::Engine;
+use super::game::{Cell, Game, GameState, Player};
+use super::record::GameRecord;
+
+/// Totals from the point of view of the two engines passed to `run`.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub games: usize,
+    pub wins: [usize; 2],
+    pub draws: usize,
+    pub plies: usize,
+    pub moves: [usize; 2],
+    pub think_time: [Duration; 2],
+}
+
+impl Stats {
+    pub fn losses(&self, engine: usize) -> usize {
+        self.wins[1 - engine]
+    }
+
+    pub fn average_length(&self) -> f64 {
+        self.plies as f64 / self.games.max(1) as f64
+    }
+
+    pub fn average_move_time(&self, engine: usize) -> Duration {
+        self.think_time[engine] / self.moves[engine].max(1) as u32
+    }
+}
+
+/// Plays one game from the empty board. An engine that has no move while the
+/// game is still in progress forfeits.
+pub fn play_game(
+    player1: &mut dyn Engine,
+    player2: &mut dyn Engine,
+    first: Player,
+) -> (GameRecord, [Duration; 2]) {
+    let mut cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+    let mut moves = Vec::new();
+    let mut think_time = [Duration::default(); 2];
+    let mut side = first;
+
+    let winner = loop {
+        let state = Game::get_game_state(&cells);
+        if state != GameState::InProgress {
+            break state.winner();
+        }
+
+        let (engine, index): (&mut dyn Engine, usize) = match side {
+            Player::Player1 => (&mut *player1, 0),
+            Player::Player2 => (&mut *player2, 1),
+        };
+        let start = Instant::now();
+        let choice = engine.choose_move(&cells, side);
+        think_time[index] += start.elapsed();
+
+        match choice {
+            Some(cell) if cells.get(cell) == Some(&Cell::Empty) => {
+                cells[cell] = Cell::Player(side);
+                moves.push(cell);
+                side = side.opponent();
+            }
+            _ => break Some(side.opponent()),
+        }
+    };
+
+    let record = GameRecord {
+        player1: player1.name(),
+        player2: player2.name(),
+        first,
+        moves,
+        winner,
+    };
+    (record, think_time)
+}
+
+/// Plays `games` games between `a` and `b`, swapping sides every game.
+/// Player1 always moves first.
+pub fn run(a: &mut dyn Engine, b: &mut dyn Engine, games: usize) -> (Stats, Vec<GameRecord>) {
+    let mut stats = Stats::default();
+    let mut records = Vec::with_capacity(games);
+
+    for game in 0..games {
+        let a_plays_first = game % 2 == 0;
+        let (record, think_time) = if a_plays_first {
+            play_game(a, b, Player::Player1)
+        } else {
+            play_game(b, a, Player::Player1)
+        };
+        // Index 0 is always engine `a`.
+        let (a_side, a_time, b_time) = if a_plays_first {
+            (Player::Player1, think_time[0], think_time[1])
+        } else {
+            (Player::Player2, think_time[1], think_time[0])
+        };
+
+        stats.games += 1;
+        match record.winner {
+            Some(player) if player == a_side => stats.wins[0] += 1,
+            Some(_) => stats.wins[1] += 1,
+            None => stats.draws += 1,
+        }
+        let a_moves = if a_plays_first {
+            record.moves.len().div_ceil(2)
+        } else {
+            record.moves.len() / 2
+        };
+        stats.plies += record.moves.len();
+        stats.moves[0] += a_moves;
+        stats.moves[1] += record.moves.len() - a_moves;
+        stats.think_time[0] += a_time;
+        stats.think_time[1] += b_time;
+        records.push(record);
+    }
+
+    (stats, records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ttt::engine::RandomEngine;
+    use crate::ttt::game_logic::{Ai, TieBreak};
+    use crate::ttt::rng::Rng;
+
+    #[test]
+    fn perfect_play_never_loses_to_random() {
+        let mut perfect = Ai::new(TieBreak::First, 1.0);
+        let mut random = RandomEngine::new(Rng::new(11));
+        let (stats, records) = run(&mut perfect, &mut random, 20);
+
+        assert_eq!(stats.games, 20);
+        assert_eq!(stats.wins[1], 0);
+        assert_eq!(stats.wins[0] + stats.draws, 20);
+        assert_eq!(records[0].player1, "perfect");
+        assert_eq!(records[1].player1, "random");
+    }
+}
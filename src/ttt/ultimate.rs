@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use super::config::ULTIMATE_AI_TIME_MS;
+use super::game::{Cell, Game, GameState, Player};
+use super::mcts::{self, Budget};
+use super::position::Position;
+use super::rng::Rng;
+
+pub const LOCAL_CELLS: usize = 9;
+
+/// Nine local 3x3 boards whose winners form a meta-board. The cell a player
+/// picks inside a local board sends the opponent to the local board in the
+/// same place, unless that board is already decided. Moves are
+/// `board * LOCAL_CELLS + cell`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UltimatePosition {
+    cells: Vec<Cell>,
+    /// Winner of each local board, kept up to date by `play`.
+    meta_cells: Vec<Cell>,
+    /// Local boards that are still in progress.
+    open_boards: Vec<bool>,
+    next_board: Option<usize>,
+    side: Player,
+}
+
+impl UltimatePosition {
+    pub fn new(first: Player) -> Self {
+        UltimatePosition {
+            cells: vec![Cell::Empty; LOCAL_CELLS * LOCAL_CELLS],
+            meta_cells: vec![Cell::Empty; LOCAL_CELLS],
+            open_boards: vec![true; LOCAL_CELLS],
+            next_board: None,
+            side: first,
+        }
+    }
+
+    pub fn local_board(&self, board: usize) -> &[Cell] {
+        &self.cells[board * LOCAL_CELLS..(board + 1) * LOCAL_CELLS]
+    }
+
+    pub fn local_state(&self, board: usize) -> GameState {
+        Game::get_game_state(self.local_board(board))
+    }
+
+    /// Boards the side to move may play in.
+    pub fn allowed_boards(&self) -> Vec<usize> {
+        if self.state() != GameState::InProgress {
+            return Vec::new();
+        }
+        match self.next_board {
+            Some(board) if self.open_boards[board] => vec![board],
+            _ => (0..LOCAL_CELLS)
+                .filter(|&board| self.open_boards[board])
+                .collect(),
+        }
+    }
+
+    fn refresh_board(&mut self, board: usize) {
+        let state = self.local_state(board);
+        if state != GameState::InProgress {
+            if let Some(player) = state.winner() {
+                self.meta_cells[board] = Cell::Player(player);
+            }
+            self.open_boards[board] = false;
+        }
+    }
+}
+
+impl Position for UltimatePosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        let mut moves = Vec::new();
+        for board in self.allowed_boards() {
+            for cell in 0..LOCAL_CELLS {
+                if self.cells[board * LOCAL_CELLS + cell] == Cell::Empty {
+                    moves.push(board * LOCAL_CELLS + cell);
+                }
+            }
+        }
+        moves
+    }
+
+    fn play(&mut self, mv: usize) {
+        self.cells[mv] = Cell::Player(self.side);
+        self.refresh_board(mv / LOCAL_CELLS);
+        self.next_board = Some(mv % LOCAL_CELLS);
+        self.side = self.side.opponent();
+    }
+
+    /// Winning cells are local board indices on the meta-board.
+    fn state(&self) -> GameState {
+        let meta_state = Game::get_game_state(&self.meta_cells);
+        if let GameState::GameWon { .. } = meta_state {
+            return meta_state;
+        }
+        if self.open_boards.contains(&true) {
+            GameState::InProgress
+        } else {
+            GameState::Tie
+        }
+    }
+}
+
+/// Ultimate tic-tac-toe against the computer. Like the classic game, the
+/// human plays `Player2` and moves first.
+pub struct UltimateGame {
+    position: UltimatePosition,
+    /// Cursor in global 9x9 coordinates.
+    selected: Option<(usize, usize)>,
+    rng: Rng,
+}
+
+impl UltimateGame {
+    pub fn new() -> Self {
+        UltimateGame {
+            position: UltimatePosition::new(Player::Player2),
+            selected: None,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.position = UltimatePosition::new(Player::Player2);
+        self.selected = None;
+    }
+
+    pub(crate) fn position(&self) -> &UltimatePosition {
+        &self.position
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, x: usize, y: usize) {
+        self.selected = Some((x.min(8), y.min(8)));
+    }
+
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected.unwrap_or((4, 4));
+        let x = (x as isize + dx).clamp(0, 8) as usize;
+        let y = (y as isize + dy).clamp(0, 8) as usize;
+        self.selected = Some((x, y));
+    }
+
+    /// Plays the human move at global coordinates; the computer answers on
+    /// the next update, once the move has been drawn.
+    pub(crate) fn play_at(&mut self, x: usize, y: usize) -> bool {
+        let mv = global_to_move(x, y);
+        if self.position.side_to_move() != Player::Player2
+            || !self.position.legal_moves().contains(&mv)
+        {
+            return false;
+        }
+        self.position.play(mv);
+        true
+    }
+
+    /// Lets the computer answer within its time if it is its turn.
+    pub(crate) fn advance(&mut self) {
+        if self.position.side_to_move() != Player::Player1
+            || self.position.state() != GameState::InProgress
+        {
+            return;
+        }
+        let budget = Budget::Time(Duration::from_millis(ULTIMATE_AI_TIME_MS));
+        if let Some(reply) = mcts::search(&self.position, budget, &mut self.rng) {
+            self.position.play(reply);
+        }
+    }
+}
+
+impl Default for UltimateGame {
+    fn default() -> Self {
+        UltimateGame::new()
+    }
+}
+
+/// Converts 9x9 board coordinates to a move.
+pub fn global_to_move(x: usize, y: usize) -> usize {
+    let board = (y / 3) * 3 + x / 3;
+    let cell = (y % 3) * 3 + x % 3;
+    board * LOCAL_CELLS + cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_sends_opponent_to_matching_board() {
+        let mut position = UltimatePosition::new(Player::Player2);
+        assert_eq!(position.legal_moves().len(), 81);
+
+        position.play(global_to_move(2, 0)); // board 0, cell 2
+        assert_eq!(position.allowed_boards(), vec![2]);
+        assert!(position
+            .legal_moves()
+            .iter()
+            .all(|mv| mv / LOCAL_CELLS == 2));
+    }
+
+    #[test]
+    fn decided_board_frees_the_opponent() {
+        let mut position = UltimatePosition::new(Player::Player1);
+        for &mv in &[0, 4, 1, 5, 2] {
+            position.cells[mv] = Cell::Player(if mv < 3 {
+                Player::Player1
+            } else {
+                Player::Player2
+            });
+        }
+        position.refresh_board(0);
+        position.next_board = Some(0);
+        assert_eq!(position.allowed_boards().len(), 8);
+        assert!(!position.allowed_boards().contains(&0));
+    }
+
+    #[test]
+    fn computer_answers_on_update() {
+        let mut game = UltimateGame::new();
+        assert!(game.play_at(4, 4));
+        assert_eq!(game.position().side_to_move(), Player::Player1);
+        game.advance();
+        assert_eq!(game.position().side_to_move(), Player::Player2);
+    }
+
+    #[test]
+    fn meta_line_wins() {
+        let mut position = UltimatePosition::new(Player::Player1);
+        for board in [0, 4, 8] {
+            for cell in 0..3 {
+                position.cells[board * LOCAL_CELLS + cell] = Cell::Player(Player::Player1);
+            }
+            position.refresh_board(board);
+        }
+        assert_eq!(
+            position.state(),
+            GameState::GameWon {
+                player: Player::Player1,
+                cells: vec![0, 4, 8]
+            }
+        );
+    }
+}
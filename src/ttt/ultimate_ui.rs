@@ -0,0 +1,160 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{ULTIMATE_FIELD_POS, ULTIMATE_SQUARE_SIZE};
+use super::game::{Cell, GameState};
+use super::game_ui::{finished_to_str, status_to_str};
+use super::position::Position;
+use super::ultimate::{UltimateGame, LOCAL_CELLS};
+
+const BOARD_SIZE: f32 = ULTIMATE_SQUARE_SIZE * 3.0;
+
+fn get_cell(x: f32, y: f32) -> Option<(usize, usize)> {
+    let cell_x = (x - ULTIMATE_FIELD_POS.0) / ULTIMATE_SQUARE_SIZE;
+    let cell_y = (y - ULTIMATE_FIELD_POS.1) / ULTIMATE_SQUARE_SIZE;
+    if (0.0..9.0).contains(&cell_x) && (0.0..9.0).contains(&cell_y) {
+        Some((cell_x as usize, cell_y as usize))
+    } else {
+        None
+    }
+}
+
+fn board_pos(board: usize) -> (f32, f32) {
+    (
+        ULTIMATE_FIELD_POS.0 + BOARD_SIZE * (board % 3) as f32,
+        ULTIMATE_FIELD_POS.1 + BOARD_SIZE * (board / 3) as f32,
+    )
+}
+
+impl Mode for UltimateGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        self.advance();
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left {
+            if let Some((cell_x, cell_y)) = get_cell(x, y) {
+                self.play_at(cell_x, cell_y);
+            }
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some((cell_x, cell_y)) = get_cell(x, y) {
+            self.select(cell_x, cell_y);
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        match keycode {
+            KeyCode::R => self.clear(),
+            KeyCode::Left => self.move_selected(-1, 0),
+            KeyCode::Right => self.move_selected(1, 0),
+            KeyCode::Up => self.move_selected(0, -1),
+            KeyCode::Down => self.move_selected(0, 1),
+            KeyCode::Space => {
+                if let Some((x, y)) = self.selected() {
+                    self.play_at(x, y);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+
+        for board in 0..LOCAL_CELLS {
+            let pos = board_pos(board);
+            painter.draw_grid(mb, pos, ULTIMATE_SQUARE_SIZE, 3, 1.5);
+            for cell in 0..LOCAL_CELLS {
+                if let Cell::Player(player) = position.local_board(board)[cell] {
+                    painter.draw_player_at(
+                        mb,
+                        pos,
+                        ULTIMATE_SQUARE_SIZE,
+                        player,
+                        cell % 3,
+                        cell / 3,
+                    );
+                }
+            }
+            if let GameState::GameWon { player, .. } = position.local_state(board) {
+                painter.draw_player_at(
+                    mb,
+                    ULTIMATE_FIELD_POS,
+                    BOARD_SIZE,
+                    player,
+                    board % 3,
+                    board / 3,
+                );
+            }
+        }
+        painter.draw_grid(mb, ULTIMATE_FIELD_POS, BOARD_SIZE, 3, 5.0);
+
+        let allowed_color = rgb(painter.theme().highlight);
+        for board in position.allowed_boards() {
+            draw_frame(
+                mb,
+                ULTIMATE_FIELD_POS,
+                BOARD_SIZE,
+                board % 3,
+                board / 3,
+                allowed_color,
+            );
+        }
+
+        let game_state = position.state();
+        match &game_state {
+            GameState::GameWon { cells, .. } => {
+                painter.draw_red_line_at(mb, ULTIMATE_FIELD_POS, BOARD_SIZE, 3, cells[0], cells[2]);
+            }
+            GameState::InProgress => {
+                if let Some((x, y)) = self.selected() {
+                    let cursor_color = rgb(painter.theme().cursor);
+                    draw_frame(
+                        mb,
+                        ULTIMATE_FIELD_POS,
+                        ULTIMATE_SQUARE_SIZE,
+                        x,
+                        y,
+                        cursor_color,
+                    );
+                }
+            }
+            _ => (),
+        }
+
+        let text = status_to_str(&game_state, "Ultimate, play in a green board");
+        painter.draw_text(ctx, &text);
+        painter.draw_sprites(ctx)?;
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        finished_to_str(&self.position().state())
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}
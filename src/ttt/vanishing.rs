@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use super::config::{PLAY_FIELD_SIZE, VANISHING_AI_DEPTH};
+use super::game::{Cell, Game, GameState, Player};
+use super::position::Position;
+
+/// Marks each player may have on the board; placing one more removes the
+/// player's oldest mark.
+pub const MAX_MARKS: usize = 3;
+
+/// Times a position may occur before the game is drawn by repetition.
+pub const REPETITION_LIMIT: usize = 3;
+
+const WIN_SCORE: i32 = 100;
+
+/// Classic board where every player keeps at most `MAX_MARKS` marks. Games
+/// need not end on their own, so a position that occurs `REPETITION_LIMIT`
+/// times is a draw.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VanishingPosition {
+    /// Live marks of `Player1` and `Player2`, oldest first.
+    marks: [Vec<usize>; 2],
+    side: Player,
+}
+
+impl VanishingPosition {
+    pub fn new(first: Player) -> Self {
+        VanishingPosition {
+            marks: [Vec::new(), Vec::new()],
+            side: first,
+        }
+    }
+
+    /// Position after playing `moves` from the empty board.
+    pub fn from_moves(moves: &[usize], first: Player) -> Self {
+        let mut position = VanishingPosition::new(first);
+        for &mv in moves {
+            position.play(mv);
+        }
+        position
+    }
+
+    pub fn cells(&self) -> Vec<Cell> {
+        let mut cells = vec![Cell::Empty; PLAY_FIELD_SIZE * PLAY_FIELD_SIZE];
+        for &player in &[Player::Player1, Player::Player2] {
+            for &id in &self.marks[player.index()] {
+                cells[id] = Cell::Player(player);
+            }
+        }
+        cells
+    }
+
+    /// The mark of `player` that goes away with their next move, if any.
+    pub fn oldest_mark(&self, player: Player) -> Option<usize> {
+        let marks = &self.marks[player.index()];
+        if marks.len() == MAX_MARKS {
+            marks.first().copied()
+        } else {
+            None
+        }
+    }
+}
+
+impl Position for VanishingPosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.state() != GameState::InProgress {
+            return Vec::new();
+        }
+        let cells = self.cells();
+        (0..cells.len())
+            .filter(|&i| cells[i] == Cell::Empty)
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        let marks = &mut self.marks[self.side.index()];
+        if marks.len() == MAX_MARKS {
+            marks.remove(0);
+        }
+        marks.push(mv);
+        self.side = self.side.opponent();
+    }
+
+    /// Never a tie: with at most six marks the board can't fill up, and
+    /// repetition is only known to `game_state`.
+    fn state(&self) -> GameState {
+        Game::get_game_state(&self.cells())
+    }
+}
+
+/// State of the game after `moves`, drawn once a position repeats
+/// `REPETITION_LIMIT` times.
+pub fn game_state(moves: &[usize], first: Player) -> GameState {
+    let mut position = VanishingPosition::new(first);
+    let mut seen = HashMap::new();
+    seen.insert(position.clone(), 1);
+    for &mv in moves {
+        position.play(mv);
+        let count = seen.entry(position.clone()).or_insert(0);
+        *count += 1;
+        if *count >= REPETITION_LIMIT {
+            return GameState::Tie;
+        }
+    }
+    position.state()
+}
+
+/// Negamax to `depth` plies. A position already on the search path scores
+/// as a draw, since repeating it can't be better than what was there before.
+fn search(
+    position: &VanishingPosition,
+    path: &mut Vec<VanishingPosition>,
+    depth: usize,
+    mut alpha: i32,
+    beta: i32,
+) -> i32 {
+    if let Some(winner) = position.state().winner() {
+        // Wins found with more depth left are sooner.
+        let score = WIN_SCORE + depth as i32;
+        return if winner == position.side {
+            score
+        } else {
+            -score
+        };
+    }
+    if depth == 0 || path.contains(position) {
+        return 0;
+    }
+
+    path.push(position.clone());
+    for mv in position.legal_moves() {
+        let mut child = position.clone();
+        child.play(mv);
+        let score = -search(&child, path, depth - 1, -beta, -alpha);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    path.pop();
+    alpha
+}
+
+/// All moves sharing the best score, looking `VANISHING_AI_DEPTH` plies
+/// ahead. `history` holds the positions reached so far in the game.
+pub fn best_moves(position: &VanishingPosition, history: &[VanishingPosition]) -> Vec<usize> {
+    let mut path = history.to_vec();
+    path.push(position.clone());
+    let mut best_score = i32::MIN;
+    let mut best_moves = Vec::new();
+    for mv in position.legal_moves() {
+        let mut child = position.clone();
+        child.play(mv);
+        let score = -search(
+            &child,
+            &mut path,
+            VANISHING_AI_DEPTH,
+            -2 * WIN_SCORE,
+            2 * WIN_SCORE,
+        );
+        if score > best_score {
+            best_score = score;
+            best_moves.clear();
+        }
+        if score == best_score {
+            best_moves.push(mv);
+        }
+    }
+    best_moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fourth_mark_removes_the_oldest() {
+        let position = VanishingPosition::from_moves(&[0, 4, 1, 5, 6, 8], Player::Player2);
+        assert_eq!(position.oldest_mark(Player::Player2), Some(0));
+        let position = VanishingPosition::from_moves(&[0, 4, 1, 5, 6, 8, 2], Player::Player2);
+        assert_eq!(position.cells()[0], Cell::Empty);
+        assert_eq!(position.state(), GameState::InProgress);
+        assert_eq!(position.oldest_mark(Player::Player2), Some(1));
+    }
+
+    #[test]
+    fn repetition_draws() {
+        // Both sides cycle their marks through four cells without a line.
+        let opening = [0, 2, 1, 3, 5, 7];
+        let cycle = [6, 8, 0, 2, 1, 3, 5, 7];
+        let mut moves = opening.to_vec();
+        assert_eq!(game_state(&moves, Player::Player2), GameState::InProgress);
+        for _ in 0..REPETITION_LIMIT {
+            moves.extend_from_slice(&cycle);
+        }
+        assert_eq!(game_state(&moves, Player::Player2), GameState::Tie);
+    }
+}
@@ -0,0 +1,248 @@
+use std::sync::OnceLock;
+
+use super::config::PLAY_FIELD_SIZE;
+use super::game::{Cell, Game, GameState, Mark, Player};
+use super::position::Position;
+use super::rng::Rng;
+
+const CELL_COUNT: usize = PLAY_FIELD_SIZE * PLAY_FIELD_SIZE;
+
+static VALUES: OnceLock<Vec<i8>> = OnceLock::new();
+
+/// Moves encode both the cell and the mark placed there.
+pub fn encode_move(cell: usize, mark: Mark) -> usize {
+    cell * 2
+        + match mark {
+            Mark::Nought => 0,
+            Mark::Cross => 1,
+        }
+}
+
+pub fn decode_move(mv: usize) -> (usize, Mark) {
+    let mark = if mv.is_multiple_of(2) {
+        Mark::Nought
+    } else {
+        Mark::Cross
+    };
+    (mv / 2, mark)
+}
+
+/// Wild tic-tac-toe: either player may place either mark, and whoever
+/// completes a line of equal marks wins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WildPosition {
+    cells: Vec<Option<Mark>>,
+    side: Player,
+}
+
+impl WildPosition {
+    pub fn new(first: Player) -> Self {
+        WildPosition {
+            cells: vec![None; CELL_COUNT],
+            side: first,
+        }
+    }
+
+    pub fn cell(&self, id: usize) -> Option<Mark> {
+        self.cells[id]
+    }
+
+    fn key(&self) -> usize {
+        self.cells.iter().fold(0, |key, cell| {
+            key * 3
+                + match cell {
+                    None => 0,
+                    Some(Mark::Nought) => 1,
+                    Some(Mark::Cross) => 2,
+                }
+        })
+    }
+}
+
+impl Position for WildPosition {
+    fn side_to_move(&self) -> Player {
+        self.side
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.state() != GameState::InProgress {
+            return Vec::new();
+        }
+        (0..CELL_COUNT)
+            .filter(|&id| self.cells[id].is_none())
+            .flat_map(|id| [encode_move(id, Mark::Nought), encode_move(id, Mark::Cross)])
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        let (id, mark) = decode_move(mv);
+        self.cells[id] = Some(mark);
+        self.side = self.side.opponent();
+    }
+
+    /// Lines are found by treating each mark as a player; the one who
+    /// completed the line is whoever moved last.
+    fn state(&self) -> GameState {
+        let cells: Vec<Cell> = self
+            .cells
+            .iter()
+            .map(|cell| match cell {
+                None => Cell::Empty,
+                Some(Mark::Nought) => Cell::Player(Player::Player1),
+                Some(Mark::Cross) => Cell::Player(Player::Player2),
+            })
+            .collect();
+        match Game::get_game_state(&cells) {
+            GameState::GameWon { cells, .. } => GameState::GameWon {
+                player: self.side.opponent(),
+                cells,
+            },
+            state => state,
+        }
+    }
+}
+
+/// Negamax value for the side to move: positive wins, sooner wins higher.
+fn solve(position: &mut WildPosition, values: &mut [Option<i8>]) -> i8 {
+    let key = position.key();
+    if let Some(value) = values[key] {
+        return value;
+    }
+    let empty = position.cells.iter().filter(|cell| cell.is_none()).count() as i8;
+    let value = match position.state() {
+        GameState::InProgress => {
+            let mut best = i8::MIN;
+            for mv in position.legal_moves() {
+                let mut child = position.clone();
+                child.play(mv);
+                best = best.max(-solve(&mut child, values));
+            }
+            best
+        }
+        GameState::Tie => 0,
+        // The side to move has just lost.
+        _ => -(empty + 1),
+    };
+    values[key] = Some(value);
+    value
+}
+
+fn values() -> &'static [i8] {
+    VALUES.get_or_init(|| {
+        let mut values = vec![None; 3usize.pow(CELL_COUNT as u32)];
+        solve(&mut WildPosition::new(Player::Player1), &mut values);
+        values.into_iter().map(|value| value.unwrap_or(0)).collect()
+    })
+}
+
+/// All moves with the best solved value.
+pub fn best_moves(position: &WildPosition) -> Vec<usize> {
+    let mut best_value = i8::MIN;
+    let mut best_moves = Vec::new();
+    for mv in position.legal_moves() {
+        let mut child = position.clone();
+        child.play(mv);
+        let value = -values()[child.key()];
+        if value > best_value {
+            best_value = value;
+            best_moves.clear();
+        }
+        if value == best_value {
+            best_moves.push(mv);
+        }
+    }
+    best_moves
+}
+
+/// Wild tic-tac-toe against the computer; the human plays `Player2` and
+/// moves first.
+pub struct WildGame {
+    position: WildPosition,
+    selected: Option<(usize, usize)>,
+    rng: Rng,
+}
+
+impl WildGame {
+    pub fn new() -> Self {
+        WildGame {
+            position: WildPosition::new(Player::Player2),
+            selected: None,
+            rng: Rng::from_time(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.position = WildPosition::new(Player::Player2);
+        self.selected = None;
+    }
+
+    pub(crate) fn position(&self) -> &WildPosition {
+        &self.position
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, usize)> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, x: usize, y: usize) {
+        let last = PLAY_FIELD_SIZE - 1;
+        self.selected = Some((x.min(last), y.min(last)));
+    }
+
+    pub(crate) fn move_selected(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected.unwrap_or((0, 0));
+        let last = PLAY_FIELD_SIZE as isize - 1;
+        let x = (x as isize + dx).clamp(0, last) as usize;
+        let y = (y as isize + dy).clamp(0, last) as usize;
+        self.selected = Some((x, y));
+    }
+
+    /// Places `mark` for the human and lets the computer answer.
+    pub(crate) fn play_at(&mut self, x: usize, y: usize, mark: Mark) -> bool {
+        let mv = encode_move(x + y * PLAY_FIELD_SIZE, mark);
+        if self.position.side_to_move() != Player::Player2
+            || !self.position.legal_moves().contains(&mv)
+        {
+            return false;
+        }
+        self.position.play(mv);
+        let replies = best_moves(&self.position);
+        if !replies.is_empty() {
+            let reply = replies[self.rng.below(replies.len())];
+            self.position.play(reply);
+        }
+        true
+    }
+}
+
+impl Default for WildGame {
+    fn default() -> Self {
+        WildGame::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn either_mark_completes_a_line() {
+        let mut position = WildPosition::new(Player::Player2);
+        for &mv in &[
+            encode_move(0, Mark::Nought),
+            encode_move(4, Mark::Cross),
+            encode_move(1, Mark::Nought),
+        ] {
+            position.play(mv);
+        }
+        // Player1 finishes the noughts Player2 started.
+        assert_eq!(best_moves(&position), vec![encode_move(2, Mark::Nought)]);
+        position.play(encode_move(2, Mark::Nought));
+        assert_eq!(position.state().winner(), Some(Player::Player1));
+    }
+
+    #[test]
+    fn first_player_wins() {
+        assert!(values()[WildPosition::new(Player::Player1).key()] > 0);
+    }
+}
@@ -0,0 +1,111 @@
+use super::draw_helpers::*;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{DrawParam, MeshBuilder},
+    Context, GameResult,
+};
+
+use super::app::Mode;
+use super::config::{PLAY_FIELD_POS, PLAY_FIELD_SIZE, SQUARE_SIZE};
+use super::game::{FieldType, Game, GameState, Mark, Topology};
+use super::game_ui::{finished_to_str, status_to_str};
+use super::position::Position;
+use super::wild::WildGame;
+
+impl Mode for WildGame {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    /// Left click places a cross, right click a nought.
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if Game::get_field_type(x, y) != FieldType::PlayField {
+            return;
+        }
+        let (cell_x, cell_y) = Game::get_cell(x, y);
+        match button {
+            MouseButton::Left => self.play_at(cell_x, cell_y, Mark::Cross),
+            MouseButton::Right => self.play_at(cell_x, cell_y, Mark::Nought),
+            _ => false,
+        };
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if Game::get_field_type(x, y) == FieldType::PlayField {
+            let (cell_x, cell_y) = Game::get_cell(x, y);
+            self.select(cell_x, cell_y);
+        }
+    }
+
+    /// Space places a cross, Shift+Space or 'O' a nought.
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        keymod: KeyMods,
+        _repeat: bool,
+    ) {
+        let mark = match keycode {
+            KeyCode::R => return self.clear(),
+            KeyCode::Left => return self.move_selected(-1, 0),
+            KeyCode::Right => return self.move_selected(1, 0),
+            KeyCode::Up => return self.move_selected(0, -1),
+            KeyCode::Down => return self.move_selected(0, 1),
+            KeyCode::Space if keymod.contains(KeyMods::SHIFT) => Mark::Nought,
+            KeyCode::Space | KeyCode::X => Mark::Cross,
+            KeyCode::O => Mark::Nought,
+            _ => return,
+        };
+        if let Some((x, y)) = self.selected() {
+            self.play_at(x, y, mark);
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, painter: &mut Painter) -> GameResult {
+        painter.clear_background(ctx);
+
+        let mb = &mut MeshBuilder::new();
+        let position = self.position();
+
+        painter.draw_field(mb, &[]);
+        for id in 0..PLAY_FIELD_SIZE * PLAY_FIELD_SIZE {
+            if let Some(mark) = position.cell(id) {
+                let (x, y) = (id % PLAY_FIELD_SIZE, id / PLAY_FIELD_SIZE);
+                painter.draw_mark_at(mb, PLAY_FIELD_POS, SQUARE_SIZE, mark, x, y);
+            }
+        }
+
+        let game_state = position.state();
+        match &game_state {
+            GameState::GameWon { cells, .. } => {
+                painter.draw_red_line(mb, cells, Topology::Flat, 1.0)
+            }
+            GameState::InProgress => {
+                if let Some((x, y)) = self.selected() {
+                    painter.draw_selected_cell(mb, x, y);
+                }
+            }
+            _ => (),
+        }
+
+        let text = status_to_str(
+            &game_state,
+            "Wild, left click or Space for X, right click or Shift+Space for O",
+        );
+        painter.draw_text(ctx, &text);
+        painter.draw_sprites(ctx)?;
+        let mbb = mb.build(ctx)?;
+        ggez::graphics::draw(ctx, &mbb, DrawParam::default())?;
+
+        Ok(())
+    }
+
+    fn result(&self) -> Option<String> {
+        finished_to_str(&self.position().state())
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+    }
+}